@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::solution::Answer;
+
+/// Accepted answers for the real puzzle inputs, kept in a small TOML file:
+///
+/// ```toml
+/// [day1]
+/// part1 = 1752
+/// part2 = 1781
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Answers(BTreeMap<(u8, u8), Answer>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail(Answer),
+    Missing,
+}
+
+impl Answers {
+    /// `answers.toml` at the root of the repository.
+    pub fn default_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+    }
+
+    pub fn load(path: &Path) -> io::Result<Answers> {
+        let contents = fs::read_to_string(path)?;
+        Answers::parse(&contents).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn parse(contents: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+        let mut day = None;
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let number = table
+                    .trim()
+                    .strip_prefix("day")
+                    .and_then(|d| d.parse::<u8>().ok())
+                    .ok_or_else(|| format!("line {}: expected a table like [day1]", i + 1))?;
+                day = Some(number);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected 'part1 = <answer>'", i + 1))?;
+            let part = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                key => return Err(format!("line {}: unknown key '{}'", i + 1, key)),
+            };
+            let day =
+                day.ok_or_else(|| format!("line {}: answer outside of a [dayN] table", i + 1))?;
+            let answer = parse_value(value.trim())
+                .ok_or_else(|| format!("line {}: expected a number or a quoted string", i + 1))?;
+
+            answers.0.insert((day, part), answer);
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.0.get(&(day, part))
+    }
+
+    pub fn record(&mut self, day: u8, part: u8, answer: Answer) {
+        self.0.insert((day, part), answer);
+    }
+
+    /// Answers compare by how they print, so a day switching between integer types still passes.
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            None => Verdict::Missing,
+            Some(expected) if expected.to_string() == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected.clone()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+fn parse_value(value: &str) -> Option<Answer> {
+    if let Some(text) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        return Some(Answer::Text(
+            text.replace("\\\"", "\"").replace("\\\\", "\\"),
+        ));
+    }
+    if let Ok(n) = value.parse::<u64>() {
+        return Some(Answer::Unsigned(n));
+    }
+    value.parse::<i64>().ok().map(Answer::Signed)
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut current_day = None;
+        for ((day, part), answer) in self.0.iter() {
+            if current_day != Some(*day) {
+                if current_day.is_some() {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[day{}]", day)?;
+                current_day = Some(*day);
+            }
+
+            match answer {
+                Answer::Text(text) => writeln!(
+                    f,
+                    "part{} = \"{}\"",
+                    part,
+                    text.replace('\\', "\\\\").replace('"', "\\\"")
+                )?,
+                answer => writeln!(f, "part{} = {}", part, answer)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answers::{Answers, Verdict};
+    use crate::solution::Answer;
+
+    const ANSWERS: &str = r#"# accepted answers
+[day1]
+part1 = 1752
+part2 = 1781
+
+[day2]
+part1 = -15
+part2 = "say \"hi\""
+"#;
+
+    #[test]
+    fn it_parses_answers() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.get(1, 1), Some(&Answer::Unsigned(1752)));
+        assert_eq!(answers.get(1, 2), Some(&Answer::Unsigned(1781)));
+        assert_eq!(answers.get(2, 1), Some(&Answer::Signed(-15)));
+        assert_eq!(
+            answers.get(2, 2),
+            Some(&Answer::Text("say \"hi\"".to_string()))
+        );
+        assert_eq!(answers.get(3, 1), None);
+    }
+
+    #[test]
+    fn it_round_trips_answers() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        let saved = answers.to_string();
+        assert_eq!(saved, ANSWERS.replace("# accepted answers\n", ""));
+        assert_eq!(Answers::parse(&saved), Ok(answers));
+    }
+
+    #[test]
+    fn it_rejects_malformed_answers() {
+        assert!(Answers::parse("part1 = 4").is_err());
+        assert!(Answers::parse("[dayone]").is_err());
+        assert!(Answers::parse("[day1]\npart3 = 4").is_err());
+        assert!(Answers::parse("[day1]\npart1 = four").is_err());
+    }
+
+    #[test]
+    fn it_checks_answers() {
+        let mut answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.check(1, 1, &Answer::Unsigned(1752)), Verdict::Pass);
+        assert_eq!(answers.check(1, 1, &Answer::Signed(1752)), Verdict::Pass);
+        assert_eq!(
+            answers.check(1, 2, &Answer::Unsigned(1)),
+            Verdict::Fail(Answer::Unsigned(1781))
+        );
+        assert_eq!(answers.check(5, 1, &Answer::Unsigned(1)), Verdict::Missing);
+
+        answers.record(5, 1, Answer::Unsigned(1));
+        assert_eq!(answers.check(5, 1, &Answer::Unsigned(1)), Verdict::Pass);
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::ParseError;
+use crate::solution::{Parts, Runner, SolveError};
+
+/// Summary of repeated timings of one step of a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Stats {
+        samples.sort_unstable();
+        let n = samples.len();
+
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+        // nearest rank, so small sample counts report a real sample rather than interpolating
+        let p95 = samples[((n * 95 + 99) / 100).max(1) - 1];
+
+        Stats {
+            min: samples[0],
+            median,
+            p95,
+        }
+    }
+}
+
+/// Timings for each step of one day: `parse`, `part1` and `part2`, and the parts that had
+/// no answer, which are timed all the same.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bench {
+    pub day: u8,
+    pub iterations: usize,
+    pub steps: Vec<(String, Stats)>,
+    pub failures: Vec<(u8, SolveError)>,
+}
+
+/// Runs both parts of a day `iterations` times.
+pub fn bench(runner: &dyn Runner, input: &str, iterations: usize) -> Result<Bench, ParseError> {
+    let mut parse = vec![];
+    let mut part1 = vec![];
+    let mut part2 = vec![];
+    let mut failures = vec![];
+
+    for iteration in 0..iterations {
+        let run = runner.run(input, Parts::Both)?;
+        parse.push(run.parse);
+        for result in run.parts {
+            if iteration == 0 {
+                if let Err(e) = result.answer {
+                    failures.push((result.part, e));
+                }
+            }
+            if result.part == 1 {
+                part1.push(result.elapsed);
+            } else {
+                part2.push(result.elapsed);
+            }
+        }
+    }
+
+    Ok(Bench {
+        day: runner.day(),
+        iterations,
+        failures,
+        steps: vec![
+            ("parse".to_string(), Stats::from_samples(&mut parse)),
+            ("part1".to_string(), Stats::from_samples(&mut part1)),
+            ("part2".to_string(), Stats::from_samples(&mut part2)),
+        ],
+    })
+}
+
+/// How a step's median moved against the baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub step: String,
+    pub baseline: Duration,
+    pub current: Duration,
+    pub change_percent: f64,
+    pub regressed: bool,
+}
+
+/// Saved bench results, one `<day> <step> <min_ns> <median_ns> <p95_ns>` row per line.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Baseline(BTreeMap<(u8, String), Stats>);
+
+impl Baseline {
+    pub fn load(path: &Path) -> io::Result<Baseline> {
+        let contents = fs::read_to_string(path)?;
+        Baseline::parse(&contents).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    pub fn parse(contents: &str) -> Result<Baseline, String> {
+        let mut baseline = Baseline::default();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.split_ascii_whitespace().collect::<Vec<&str>>();
+            let invalid = || {
+                format!(
+                    "line {}: expected '<day> <step> <min> <median> <p95>'",
+                    i + 1
+                )
+            };
+            if fields.len() != 5 {
+                return Err(invalid());
+            }
+
+            let day = fields[0].parse::<u8>().map_err(|_| invalid())?;
+            let mut nanos = [0u64; 3];
+            for (n, field) in nanos.iter_mut().zip(fields[2..].iter()) {
+                *n = field.parse::<u64>().map_err(|_| invalid())?;
+            }
+
+            baseline.0.insert(
+                (day, fields[1].to_string()),
+                Stats {
+                    min: Duration::from_nanos(nanos[0]),
+                    median: Duration::from_nanos(nanos[1]),
+                    p95: Duration::from_nanos(nanos[2]),
+                },
+            );
+        }
+
+        Ok(baseline)
+    }
+
+    /// Replaces any saved results for the bench's day.
+    pub fn record(&mut self, bench: &Bench) {
+        for (step, stats) in bench.steps.iter() {
+            self.0.insert((bench.day, step.clone()), *stats);
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Compares medians, flagging any step more than `threshold_percent` slower than saved.
+    pub fn compare(&self, bench: &Bench, threshold_percent: f64) -> Vec<Comparison> {
+        bench
+            .steps
+            .iter()
+            .filter_map(|(step, stats)| {
+                let saved = self.0.get(&(bench.day, step.clone()))?;
+                let baseline = saved.median.as_nanos() as f64;
+                let current = stats.median.as_nanos() as f64;
+                let change_percent = if baseline > 0.0 {
+                    (current - baseline) / baseline * 100.0
+                } else {
+                    0.0
+                };
+
+                Some(Comparison {
+                    step: step.clone(),
+                    baseline: saved.median,
+                    current: stats.median,
+                    change_percent,
+                    regressed: change_percent > threshold_percent,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# day step min_ns median_ns p95_ns")?;
+        for ((day, step), stats) in self.0.iter() {
+            writeln!(
+                f,
+                "{} {} {} {} {}",
+                day,
+                step,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.p95.as_nanos()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::bench::{bench, Baseline, Bench, Stats};
+    use crate::day4::Day4;
+    use crate::day6::Day6;
+    use crate::solution::SolveError;
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    fn stats(min: u64, median: u64, p95: u64) -> Stats {
+        Stats {
+            min: Duration::from_millis(min),
+            median: Duration::from_millis(median),
+            p95: Duration::from_millis(p95),
+        }
+    }
+
+    #[test]
+    fn it_summarizes_samples() {
+        assert_eq!(Stats::from_samples(&mut millis(&[5, 1, 3])), stats(1, 3, 5));
+        assert_eq!(
+            Stats::from_samples(&mut millis(&[4, 1, 3, 2])),
+            Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_micros(2500),
+                p95: Duration::from_millis(4),
+            }
+        );
+
+        let mut samples = millis(&(1..=100).collect::<Vec<u64>>());
+        assert_eq!(
+            Stats::from_samples(&mut samples).p95,
+            Duration::from_millis(95)
+        );
+    }
+
+    #[test]
+    fn it_benches_every_step() {
+        let result = bench(&Day6, "3,4,3,1,2", 3).unwrap();
+        assert_eq!(result.day, 6);
+        assert_eq!(result.iterations, 3);
+        assert_eq!(
+            result
+                .steps
+                .iter()
+                .map(|(step, _)| step.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["parse", "part1", "part2"]
+        );
+        assert_eq!(result.failures, vec![]);
+    }
+
+    #[test]
+    fn it_benches_parts_without_answers() {
+        let game = "1\n\n1 2 3 4 5\n6 7 8 9 10\n11 12 13 14 15\n16 17 18 19 20\n21 22 23 24 25";
+        let result = bench(&Day4, game, 2).unwrap();
+        assert_eq!(result.steps.len(), 3);
+        assert_eq!(
+            result.failures,
+            vec![
+                (
+                    1,
+                    SolveError::NoAnswer("no board won the tournament".to_string())
+                ),
+                (
+                    2,
+                    SolveError::NoAnswer("not every board won the tournament".to_string())
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn baselines_round_trip() {
+        let mut baseline = Baseline::default();
+        baseline.record(&Bench {
+            day: 7,
+            iterations: 10,
+            steps: vec![("part2".to_string(), stats(1, 2, 3))],
+            failures: vec![],
+        });
+
+        let saved = baseline.to_string();
+        assert_eq!(
+            saved,
+            "# day step min_ns median_ns p95_ns\n7 part2 1000000 2000000 3000000\n"
+        );
+        assert_eq!(Baseline::parse(&saved), Ok(baseline));
+
+        assert!(Baseline::parse("7 part2 1 2").is_err());
+    }
+
+    #[test]
+    fn it_flags_regressions() {
+        let baseline = Baseline::parse("7 part1 9 10 11\n7 part2 9 10 11").unwrap();
+        let mut current = Bench {
+            day: 7,
+            iterations: 10,
+            steps: vec![
+                ("parse".to_string(), stats(1, 1, 1)),
+                ("part1".to_string(), stats(0, 0, 0)),
+                ("part2".to_string(), stats(0, 0, 0)),
+            ],
+            failures: vec![],
+        };
+        current.steps[1].1.median = Duration::from_nanos(11);
+        current.steps[2].1.median = Duration::from_nanos(12);
+
+        let comparisons = baseline.compare(&current, 15.0);
+        assert_eq!(comparisons.len(), 2);
+        assert_eq!(comparisons[0].step, "part1");
+        assert!(!comparisons[0].regressed);
+        assert_eq!(comparisons[1].step, "part2");
+        assert!(comparisons[1].regressed);
+        assert!((comparisons[1].change_percent - 20.0).abs() < 1e-9);
+    }
+}
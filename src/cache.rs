@@ -0,0 +1,191 @@
+use std::env;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::fetch::{FetchError, Fetcher};
+
+pub const YEAR: u16 = 2021;
+
+/// Overrides where downloaded inputs are cached.
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+/// Why [`InputCache::get`] has no input: reading or writing the cache failed, or the input
+/// wasn't cached and couldn't be downloaded.
+#[derive(Debug)]
+pub enum CacheError {
+    Io(io::Error),
+    Fetch(FetchError),
+}
+
+impl Display for CacheError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheError::Io(e) => write!(f, "{}", e),
+            CacheError::Fetch(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for CacheError {}
+
+impl From<io::Error> for CacheError {
+    fn from(e: io::Error) -> CacheError {
+        CacheError::Io(e)
+    }
+}
+
+impl From<FetchError> for CacheError {
+    fn from(e: FetchError) -> CacheError {
+        CacheError::Fetch(e)
+    }
+}
+
+/// Puzzle inputs stored as `<dir>/<year>/day<N>.txt`, downloaded on first use.
+pub struct InputCache<F: Fetcher> {
+    dir: PathBuf,
+    year: u16,
+    fetcher: F,
+}
+
+impl<F: Fetcher> InputCache<F> {
+    pub fn new(dir: PathBuf, year: u16, fetcher: F) -> InputCache<F> {
+        InputCache { dir, year, fetcher }
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir
+            .join(self.year.to_string())
+            .join(format!("day{}.txt", day))
+    }
+
+    /// The cached input, downloading and saving it first if it isn't cached yet.
+    pub fn get(&self, day: u8) -> Result<String, CacheError> {
+        let path = self.path(day);
+        if path.exists() {
+            return Ok(fs::read_to_string(path)?);
+        }
+
+        let input = self.fetcher.fetch(self.year, day)?;
+        fs::create_dir_all(path.parent().unwrap())?;
+        // write then rename so an interrupted download never looks like a cached input
+        let partial = path.with_extension("txt.part");
+        fs::write(&partial, &input)?;
+        fs::rename(&partial, &path)?;
+        Ok(input)
+    }
+}
+
+/// `$AOC_CACHE_DIR`, otherwise `aoc` in the user's cache directory.
+pub fn default_dir() -> PathBuf {
+    cache_dir(
+        env::var_os(CACHE_DIR_VAR).map(PathBuf::from),
+        env::var_os("XDG_CACHE_HOME").map(PathBuf::from),
+        env::var_os("HOME").map(PathBuf::from),
+    )
+}
+
+fn cache_dir(
+    cache_dir: Option<PathBuf>,
+    xdg_cache: Option<PathBuf>,
+    home: Option<PathBuf>,
+) -> PathBuf {
+    match (cache_dir, xdg_cache, home) {
+        (Some(dir), _, _) => dir,
+        (None, Some(xdg), _) => xdg.join("aoc"),
+        (None, None, Some(home)) => home.join(".cache").join("aoc"),
+        (None, None, None) => env::temp_dir().join("aoc"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::cache::{cache_dir, CacheError, InputCache};
+    use crate::fetch::{FetchError, Fetcher};
+
+    struct CountingFetcher {
+        calls: Cell<u32>,
+    }
+
+    impl Fetcher for CountingFetcher {
+        fn fetch(&self, year: u16, day: u8) -> Result<String, FetchError> {
+            self.calls.set(self.calls.get() + 1);
+            Ok(format!("{} {}\n", year, day))
+        }
+    }
+
+    struct OfflineFetcher;
+
+    impl Fetcher for OfflineFetcher {
+        fn fetch(&self, _year: u16, _day: u8) -> Result<String, FetchError> {
+            Err(FetchError::MissingSession)
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc2021-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn it_downloads_once_then_reads_the_cache() {
+        let dir = temp_dir("cache-miss");
+        let cache = InputCache::new(
+            dir.clone(),
+            2021,
+            CountingFetcher {
+                calls: Cell::new(0),
+            },
+        );
+
+        assert_eq!(cache.path(9), dir.join("2021").join("day9.txt"));
+        assert_eq!(cache.get(9).unwrap(), "2021 9\n");
+        assert_eq!(cache.get(9).unwrap(), "2021 9\n");
+        assert_eq!(cache.fetcher.calls.get(), 1);
+        assert_eq!(fs::read_to_string(cache.path(9)).unwrap(), "2021 9\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cache_hits_never_fetch() {
+        let dir = temp_dir("cache-hit");
+        fs::create_dir_all(dir.join("2021")).unwrap();
+        fs::write(dir.join("2021").join("day3.txt"), "00100\n").unwrap();
+
+        let cache = InputCache::new(dir.clone(), 2021, OfflineFetcher);
+        assert_eq!(cache.get(3).unwrap(), "00100\n");
+        assert!(matches!(
+            cache.get(4),
+            Err(CacheError::Fetch(FetchError::MissingSession))
+        ));
+        assert!(!cache.path(4).exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn it_picks_a_cache_dir() {
+        let some = |p: &str| Some(PathBuf::from(p));
+        assert_eq!(
+            cache_dir(some("/aoc"), some("/xdg"), some("/home/me")),
+            PathBuf::from("/aoc")
+        );
+        assert_eq!(
+            cache_dir(None, some("/xdg"), some("/home/me")),
+            PathBuf::from("/xdg/aoc")
+        );
+        assert_eq!(
+            cache_dir(None, None, some("/home/me")),
+            PathBuf::from("/home/me/.cache/aoc")
+        );
+    }
+}
@@ -0,0 +1,756 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use aoc2021::day3::RulePolicy;
+use aoc2021::input::InputSource;
+use aoc2021::solution::Parts;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(Args),
+    Bench(BenchArgs),
+    Verify(VerifyArgs),
+    New(u8),
+    Trace(TraceArgs),
+    Explain(ExplainArgs),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub days: Vec<u8>,
+    pub parts: Parts,
+    pub input: Option<InputSource>,
+    pub time: bool,
+    pub format: Format,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchArgs {
+    pub day: u8,
+    pub input: Option<InputSource>,
+    pub iterations: usize,
+    pub baseline: Option<PathBuf>,
+    pub save_baseline: Option<PathBuf>,
+    pub threshold: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyArgs {
+    pub days: Vec<u8>,
+    pub answers: Option<PathBuf>,
+    pub record: bool,
+}
+
+/// Which day 2 navigation models to trace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Model {
+    Plain,
+    Aimed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceFormat {
+    Csv,
+    Svg,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TraceArgs {
+    pub input: Option<InputSource>,
+    pub models: Vec<Model>,
+    pub format: TraceFormat,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExplainArgs {
+    pub input: Option<InputSource>,
+    pub format: Format,
+    /// One policy for every rate and rating, or `None` for the puzzle's rules.
+    pub policy: Option<RulePolicy>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    UnknownDay(u8, Vec<u8>),
+    InvalidDays(String),
+    InvalidPart(String),
+    InvalidFormat(String),
+    InvalidTraceFormat(String),
+    InvalidModel(String),
+    InvalidPolicy(String),
+    InvalidNumber(String, String),
+    MissingValue(String),
+    MissingDay(String),
+    UnexpectedArgument(String),
+    InputForManyDays,
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::UnknownDay(day, available) => {
+                let available = available
+                    .iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "day {} is not implemented, available days: {}",
+                    day, available
+                )
+            }
+            CliError::InvalidDays(days) => write!(
+                f,
+                "invalid day selection '{}', expected a day, a range like 3..=6, or 'all'",
+                days
+            ),
+            CliError::InvalidPart(part) => {
+                write!(f, "invalid part '{}', expected 1 or 2", part)
+            }
+            CliError::InvalidFormat(format) => {
+                write!(f, "invalid format '{}', expected text or json", format)
+            }
+            CliError::InvalidTraceFormat(format) => {
+                write!(f, "invalid format '{}', expected csv or svg", format)
+            }
+            CliError::InvalidModel(model) => {
+                write!(f, "invalid model '{}', expected plain or aimed", model)
+            }
+            CliError::InvalidPolicy(policy) => write!(
+                f,
+                "invalid policy '{}', expected strict, tie-to-one, tie-to-zero or threshold:<percent>",
+                policy
+            ),
+            CliError::InvalidNumber(flag, value) => {
+                write!(
+                    f,
+                    "invalid value '{}' for {}, expected a number",
+                    value, flag
+                )
+            }
+            CliError::MissingValue(flag) => write!(f, "{} requires a value", flag),
+            CliError::MissingDay(command) => write!(f, "{} requires a day", command),
+            CliError::UnexpectedArgument(arg) => write!(f, "unexpected argument '{}'", arg),
+            CliError::InputForManyDays => write!(f, "--input can only be used with a single day"),
+        }
+    }
+}
+
+pub const USAGE: &str = "usage: aoc2021 [all | <day> | <from>..<to> | <from>..=<to>] [--part <1|2>] [--input <path|->] [--time] [--format <text|json>]
+       aoc2021 bench <day> [--input <path|->] [--iterations <n>] [--baseline <file>] [--save-baseline <file>] [--threshold <percent>]
+       aoc2021 verify [all | <day> | <from>..<to> | <from>..=<to>] [--answers <file>] [--record]
+       aoc2021 new <day>
+       aoc2021 trace [--input <path|->] [--model <plain|aimed>] [--format <csv|svg>]
+       aoc2021 explain [--input <path|->] [--format <text|json>] [--policy <strict|tie-to-one|tie-to-zero|threshold:<percent>>]";
+
+/// Parses the command line (without the program name) against the days that exist.
+pub fn parse_args<I: Iterator<Item = String>>(
+    args: I,
+    available: &[u8],
+) -> Result<Command, CliError> {
+    let mut args = args.peekable();
+    match args.peek().map(|a| a.as_str()) {
+        Some("bench") => {
+            args.next();
+            Ok(Command::Bench(parse_bench(args, available)?))
+        }
+        Some("verify") => {
+            args.next();
+            Ok(Command::Verify(parse_verify(args, available)?))
+        }
+        Some("new") => {
+            args.next();
+            Ok(Command::New(parse_new(args)?))
+        }
+        Some("trace") => {
+            args.next();
+            Ok(Command::Trace(parse_trace(args)?))
+        }
+        Some("explain") => {
+            args.next();
+            Ok(Command::Explain(parse_explain(args)?))
+        }
+        _ => Ok(Command::Run(parse_run(args, available)?)),
+    }
+}
+
+fn parse_run<I: Iterator<Item = String>>(mut args: I, available: &[u8]) -> Result<Args, CliError> {
+    let mut selection: Option<String> = None;
+    let mut parts = Parts::Both;
+    let mut input = None;
+    let mut time = false;
+    let mut format = Format::Text;
+
+    while let Some(arg) = args.next() {
+        if let Some(value) = flag_value(&arg, &["--part", "-p"], &mut args)? {
+            parts = parse_part(&value)?;
+        } else if let Some(value) = flag_value(&arg, &["--format", "-f"], &mut args)? {
+            format = parse_format(&value)?;
+        } else if let Some(value) = flag_value(&arg, &["--input", "-i"], &mut args)? {
+            input = Some(InputSource::from_arg(&value));
+        } else if arg == "--time" || arg == "-t" {
+            time = true;
+        } else if selection.is_none() && !arg.starts_with('-') {
+            selection = Some(arg);
+        } else {
+            return Err(CliError::UnexpectedArgument(arg));
+        }
+    }
+
+    let days = match selection {
+        None => available.to_vec(),
+        Some(selection) => parse_days(&selection, available)?,
+    };
+
+    if input.is_some() && days.len() > 1 {
+        return Err(CliError::InputForManyDays);
+    }
+
+    Ok(Args {
+        days,
+        parts,
+        input,
+        time,
+        format,
+    })
+}
+
+fn parse_bench<I: Iterator<Item = String>>(
+    mut args: I,
+    available: &[u8],
+) -> Result<BenchArgs, CliError> {
+    let mut day = None;
+    let mut input = None;
+    let mut iterations = 100;
+    let mut baseline = None;
+    let mut save_baseline = None;
+    let mut threshold = 10.0;
+
+    while let Some(arg) = args.next() {
+        if let Some(value) = flag_value(&arg, &["--input", "-i"], &mut args)? {
+            input = Some(InputSource::from_arg(&value));
+        } else if let Some(value) = flag_value(&arg, &["--iterations", "-n"], &mut args)? {
+            iterations = parse_number(&arg, &value)?;
+        } else if let Some(value) = flag_value(&arg, &["--baseline"], &mut args)? {
+            baseline = Some(PathBuf::from(value));
+        } else if let Some(value) = flag_value(&arg, &["--save-baseline"], &mut args)? {
+            save_baseline = Some(PathBuf::from(value));
+        } else if let Some(value) = flag_value(&arg, &["--threshold"], &mut args)? {
+            threshold = parse_number(&arg, &value)?;
+        } else if day.is_none() && !arg.starts_with('-') {
+            let days = parse_days(&arg, available)?;
+            if days.len() != 1 {
+                return Err(CliError::UnexpectedArgument(arg));
+            }
+            day = Some(days[0]);
+        } else {
+            return Err(CliError::UnexpectedArgument(arg));
+        }
+    }
+
+    if iterations == 0 {
+        return Err(CliError::InvalidNumber(
+            "--iterations".to_string(),
+            "0".to_string(),
+        ));
+    }
+
+    Ok(BenchArgs {
+        day: day.ok_or_else(|| CliError::MissingDay("bench".to_string()))?,
+        input,
+        iterations,
+        baseline,
+        save_baseline,
+        threshold,
+    })
+}
+
+fn parse_verify<I: Iterator<Item = String>>(
+    mut args: I,
+    available: &[u8],
+) -> Result<VerifyArgs, CliError> {
+    let mut selection: Option<String> = None;
+    let mut answers = None;
+    let mut record = false;
+
+    while let Some(arg) = args.next() {
+        if let Some(value) = flag_value(&arg, &["--answers"], &mut args)? {
+            answers = Some(PathBuf::from(value));
+        } else if arg == "--record" {
+            record = true;
+        } else if selection.is_none() && !arg.starts_with('-') {
+            selection = Some(arg);
+        } else {
+            return Err(CliError::UnexpectedArgument(arg));
+        }
+    }
+
+    let days = match selection {
+        None => available.to_vec(),
+        Some(selection) => parse_days(&selection, available)?,
+    };
+
+    Ok(VerifyArgs {
+        days,
+        answers,
+        record,
+    })
+}
+
+fn parse_new<I: Iterator<Item = String>>(mut args: I) -> Result<u8, CliError> {
+    let arg = args
+        .next()
+        .ok_or_else(|| CliError::MissingDay("new".to_string()))?;
+    let day = match arg.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => day,
+        _ => return Err(CliError::InvalidDays(arg)),
+    };
+
+    match args.next() {
+        Some(extra) => Err(CliError::UnexpectedArgument(extra)),
+        None => Ok(day),
+    }
+}
+
+/// Traces day 2, so there's no day to select. Both models are traced unless `--model` picks one.
+fn parse_trace<I: Iterator<Item = String>>(mut args: I) -> Result<TraceArgs, CliError> {
+    let mut input = None;
+    let mut models = vec![];
+    let mut format = TraceFormat::Csv;
+
+    while let Some(arg) = args.next() {
+        if let Some(value) = flag_value(&arg, &["--input", "-i"], &mut args)? {
+            input = Some(InputSource::from_arg(&value));
+        } else if let Some(value) = flag_value(&arg, &["--model", "-m"], &mut args)? {
+            let model = match value.as_str() {
+                "plain" => Model::Plain,
+                "aimed" => Model::Aimed,
+                _ => return Err(CliError::InvalidModel(value)),
+            };
+            if !models.contains(&model) {
+                models.push(model);
+            }
+        } else if let Some(value) = flag_value(&arg, &["--format", "-f"], &mut args)? {
+            format = match value.as_str() {
+                "csv" => TraceFormat::Csv,
+                "svg" => TraceFormat::Svg,
+                _ => return Err(CliError::InvalidTraceFormat(value)),
+            };
+        } else {
+            return Err(CliError::UnexpectedArgument(arg));
+        }
+    }
+
+    if models.is_empty() {
+        models = vec![Model::Plain, Model::Aimed];
+    }
+
+    Ok(TraceArgs {
+        input,
+        models,
+        format,
+    })
+}
+
+/// Explains day 3, so there's no day to select.
+fn parse_explain<I: Iterator<Item = String>>(mut args: I) -> Result<ExplainArgs, CliError> {
+    let mut input = None;
+    let mut format = Format::Text;
+    let mut policy = None;
+
+    while let Some(arg) = args.next() {
+        if let Some(value) = flag_value(&arg, &["--input", "-i"], &mut args)? {
+            input = Some(InputSource::from_arg(&value));
+        } else if let Some(value) = flag_value(&arg, &["--format", "-f"], &mut args)? {
+            format = parse_format(&value)?;
+        } else if let Some(value) = flag_value(&arg, &["--policy"], &mut args)? {
+            policy = Some(parse_policy(&value)?);
+        } else {
+            return Err(CliError::UnexpectedArgument(arg));
+        }
+    }
+
+    Ok(ExplainArgs {
+        input,
+        format,
+        policy,
+    })
+}
+
+/// The value of `arg` if it is one of `names`, given as either `--name value` or `--name=value`.
+fn flag_value<I: Iterator<Item = String>>(
+    arg: &str,
+    names: &[&str],
+    args: &mut I,
+) -> Result<Option<String>, CliError> {
+    for name in names {
+        if arg == *name {
+            return args
+                .next()
+                .map(Some)
+                .ok_or_else(|| CliError::MissingValue(arg.to_string()));
+        }
+        if let Some(value) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            return Ok(Some(value.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, CliError> {
+    let flag = flag.split('=').next().unwrap_or(flag);
+    value
+        .parse::<T>()
+        .map_err(|_| CliError::InvalidNumber(flag.to_string(), value.to_string()))
+}
+
+fn parse_part(value: &str) -> Result<Parts, CliError> {
+    match value {
+        "1" => Ok(Parts::One),
+        "2" => Ok(Parts::Two),
+        _ => Err(CliError::InvalidPart(value.to_string())),
+    }
+}
+
+fn parse_format(value: &str) -> Result<Format, CliError> {
+    match value {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(CliError::InvalidFormat(value.to_string())),
+    }
+}
+
+/// A threshold is a whole percentage, from 0 to 100.
+fn parse_policy(value: &str) -> Result<RulePolicy, CliError> {
+    let threshold = value
+        .strip_prefix("threshold:")
+        .and_then(|percent| percent.parse::<u32>().ok());
+    match (value, threshold) {
+        ("strict", _) => Ok(RulePolicy::StrictMajority),
+        ("tie-to-one", _) => Ok(RulePolicy::TieToOne),
+        ("tie-to-zero", _) => Ok(RulePolicy::TieToZero),
+        (_, Some(percent)) if percent <= 100 => Ok(RulePolicy::Threshold(percent)),
+        _ => Err(CliError::InvalidPolicy(value.to_string())),
+    }
+}
+
+fn parse_days(selection: &str, available: &[u8]) -> Result<Vec<u8>, CliError> {
+    if selection == "all" {
+        return Ok(available.to_vec());
+    }
+
+    let parse_day = |s: &str| {
+        s.trim()
+            .parse::<u8>()
+            .map_err(|_| CliError::InvalidDays(selection.to_string()))
+    };
+
+    let days: Vec<u8> = if let Some((from, to)) = selection.split_once("..=") {
+        (parse_day(from)?..=parse_day(to)?).collect()
+    } else if let Some((from, to)) = selection.split_once("..") {
+        (parse_day(from)?..parse_day(to)?).collect()
+    } else {
+        vec![parse_day(selection)?]
+    };
+
+    if days.is_empty() {
+        return Err(CliError::InvalidDays(selection.to_string()));
+    }
+
+    for &day in days.iter() {
+        if !available.contains(&day) {
+            return Err(CliError::UnknownDay(day, available.to_vec()));
+        }
+    }
+
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use aoc2021::day3::RulePolicy;
+    use aoc2021::input::InputSource;
+    use aoc2021::solution::Parts;
+
+    use crate::cli::{
+        parse_args, Args, BenchArgs, CliError, Command, ExplainArgs, Format, Model, TraceArgs,
+        TraceFormat, VerifyArgs,
+    };
+
+    const AVAILABLE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    fn command(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|a| a.to_string()), &AVAILABLE)
+    }
+
+    fn parse(args: &[&str]) -> Result<Args, CliError> {
+        match command(args)? {
+            Command::Run(args) => Ok(args),
+            command => panic!("expected a run, got {:?}", command),
+        }
+    }
+
+    fn bench(args: &[&str]) -> Result<BenchArgs, CliError> {
+        match command(args)? {
+            Command::Bench(args) => Ok(args),
+            command => panic!("expected a bench, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn it_runs_everything_by_default() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.days, AVAILABLE.to_vec());
+        assert_eq!(args.parts, Parts::Both);
+        assert_eq!(args.input, None);
+        assert!(!args.time);
+        assert_eq!(args.format, Format::Text);
+
+        assert_eq!(parse(&["all"]).unwrap().days, AVAILABLE.to_vec());
+    }
+
+    #[test]
+    fn it_selects_a_day_and_part() {
+        assert_eq!(parse(&["5"]).unwrap().days, vec![5]);
+
+        let args = parse(&["5", "--part", "2"]).unwrap();
+        assert_eq!(args.days, vec![5]);
+        assert_eq!(args.parts, Parts::Two);
+
+        assert_eq!(parse(&["--part=1", "3"]).unwrap().parts, Parts::One);
+        assert!(parse(&["3", "--time"]).unwrap().time);
+        assert_eq!(
+            parse(&["3", "--format", "json"]).unwrap().format,
+            Format::Json
+        );
+    }
+
+    #[test]
+    fn it_selects_ranges() {
+        assert_eq!(parse(&["3..=6"]).unwrap().days, vec![3, 4, 5, 6]);
+        assert_eq!(parse(&["3..6"]).unwrap().days, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn it_takes_an_input_for_a_single_day() {
+        let args = parse(&["3", "--input", "my/day3.txt"]).unwrap();
+        assert_eq!(
+            args.input,
+            Some(InputSource::File(PathBuf::from("my/day3.txt")))
+        );
+
+        let args = parse(&["--input=-", "3"]).unwrap();
+        assert_eq!(args.input, Some(InputSource::Stdin));
+
+        assert_eq!(
+            parse(&["3..=4", "--input", "-"]).unwrap_err(),
+            CliError::InputForManyDays
+        );
+    }
+
+    #[test]
+    fn it_parses_bench_commands() {
+        let args = bench(&["bench", "7"]).unwrap();
+        assert_eq!(
+            args,
+            BenchArgs {
+                day: 7,
+                input: None,
+                iterations: 100,
+                baseline: None,
+                save_baseline: None,
+                threshold: 10.0,
+            }
+        );
+
+        let args = bench(&[
+            "bench",
+            "--iterations=20",
+            "3",
+            "--baseline",
+            "bench.txt",
+            "--save-baseline",
+            "new.txt",
+            "--threshold",
+            "5",
+        ])
+        .unwrap();
+        assert_eq!(args.day, 3);
+        assert_eq!(args.iterations, 20);
+        assert_eq!(args.baseline, Some(PathBuf::from("bench.txt")));
+        assert_eq!(args.save_baseline, Some(PathBuf::from("new.txt")));
+        assert_eq!(args.threshold, 5.0);
+    }
+
+    #[test]
+    fn it_rejects_bad_bench_commands() {
+        assert_eq!(
+            bench(&["bench"]).unwrap_err(),
+            CliError::MissingDay("bench".to_string())
+        );
+        assert_eq!(
+            bench(&["bench", "2", "--iterations", "lots"]).unwrap_err(),
+            CliError::InvalidNumber("--iterations".to_string(), "lots".to_string())
+        );
+        assert!(matches!(
+            bench(&["bench", "1..=3"]),
+            Err(CliError::UnexpectedArgument(_))
+        ));
+        assert!(matches!(
+            bench(&["bench", "9"]),
+            Err(CliError::UnknownDay(9, _))
+        ));
+    }
+
+    #[test]
+    fn it_parses_verify_commands() {
+        assert_eq!(
+            command(&["verify"]).unwrap(),
+            Command::Verify(VerifyArgs {
+                days: AVAILABLE.to_vec(),
+                answers: None,
+                record: false,
+            })
+        );
+        assert_eq!(
+            command(&["verify", "2..=3", "--answers=mine.toml", "--record"]).unwrap(),
+            Command::Verify(VerifyArgs {
+                days: vec![2, 3],
+                answers: Some(PathBuf::from("mine.toml")),
+                record: true,
+            })
+        );
+        assert!(matches!(
+            command(&["verify", "--input", "x.txt"]),
+            Err(CliError::UnexpectedArgument(_))
+        ));
+    }
+
+    #[test]
+    fn it_parses_new_commands() {
+        assert_eq!(command(&["new", "9"]).unwrap(), Command::New(9));
+        assert_eq!(
+            command(&["new"]).unwrap_err(),
+            CliError::MissingDay("new".to_string())
+        );
+        assert!(matches!(
+            command(&["new", "26"]),
+            Err(CliError::InvalidDays(_))
+        ));
+        assert!(matches!(
+            command(&["new", "9", "10"]),
+            Err(CliError::UnexpectedArgument(_))
+        ));
+    }
+
+    #[test]
+    fn it_parses_trace_commands() {
+        assert_eq!(
+            command(&["trace"]).unwrap(),
+            Command::Trace(TraceArgs {
+                input: None,
+                models: vec![Model::Plain, Model::Aimed],
+                format: TraceFormat::Csv,
+            })
+        );
+        assert_eq!(
+            command(&["trace", "--model", "aimed", "--format=svg", "-i", "-"]).unwrap(),
+            Command::Trace(TraceArgs {
+                input: Some(InputSource::Stdin),
+                models: vec![Model::Aimed],
+                format: TraceFormat::Svg,
+            })
+        );
+        assert_eq!(
+            command(&["trace", "--model", "diagonal"]).unwrap_err(),
+            CliError::InvalidModel("diagonal".to_string())
+        );
+        assert_eq!(
+            command(&["trace", "--format", "json"]).unwrap_err(),
+            CliError::InvalidTraceFormat("json".to_string())
+        );
+        assert!(matches!(
+            command(&["trace", "2"]),
+            Err(CliError::UnexpectedArgument(_))
+        ));
+    }
+
+    #[test]
+    fn it_parses_explain_commands() {
+        assert_eq!(
+            command(&["explain"]).unwrap(),
+            Command::Explain(ExplainArgs {
+                input: None,
+                format: Format::Text,
+                policy: None,
+            })
+        );
+        assert_eq!(
+            command(&["explain", "--format=json", "--input", "report.txt"]).unwrap(),
+            Command::Explain(ExplainArgs {
+                input: Some(InputSource::File(PathBuf::from("report.txt"))),
+                format: Format::Json,
+                policy: None,
+            })
+        );
+        assert_eq!(
+            command(&["explain", "--format", "svg"]).unwrap_err(),
+            CliError::InvalidFormat("svg".to_string())
+        );
+
+        let policy = |value: &str| match command(&["explain", "--policy", value])? {
+            Command::Explain(args) => Ok(args.policy),
+            command => panic!("expected an explain, got {:?}", command),
+        };
+        assert_eq!(policy("strict"), Ok(Some(RulePolicy::StrictMajority)));
+        assert_eq!(policy("tie-to-one"), Ok(Some(RulePolicy::TieToOne)));
+        assert_eq!(policy("tie-to-zero"), Ok(Some(RulePolicy::TieToZero)));
+        assert_eq!(policy("threshold:60"), Ok(Some(RulePolicy::Threshold(60))));
+        for bad in ["lenient", "threshold:", "threshold:101", "threshold:-5"] {
+            assert_eq!(policy(bad), Err(CliError::InvalidPolicy(bad.to_string())));
+        }
+    }
+
+    #[test]
+    fn it_rejects_unknown_days() {
+        let err = parse(&["12"]).unwrap_err();
+        assert_eq!(err, CliError::UnknownDay(12, AVAILABLE.to_vec()));
+        assert_eq!(
+            err.to_string(),
+            "day 12 is not implemented, available days: 1, 2, 3, 4, 5, 6, 7, 8"
+        );
+
+        assert!(matches!(parse(&["7..=9"]), Err(CliError::UnknownDay(9, _))));
+    }
+
+    #[test]
+    fn it_rejects_bad_arguments() {
+        assert!(matches!(parse(&["five"]), Err(CliError::InvalidDays(_))));
+        assert!(matches!(parse(&["6..3"]), Err(CliError::InvalidDays(_))));
+        assert!(matches!(
+            parse(&["1", "--part", "3"]),
+            Err(CliError::InvalidPart(_))
+        ));
+        assert!(matches!(
+            parse(&["1", "--format=yaml"]),
+            Err(CliError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            parse(&["1", "--part"]),
+            Err(CliError::MissingValue(_))
+        ));
+        assert!(matches!(
+            parse(&["1", "2"]),
+            Err(CliError::UnexpectedArgument(_))
+        ));
+    }
+}
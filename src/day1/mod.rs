@@ -1,71 +1,501 @@
-use std::fs;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::BufRead;
 use std::str::Lines;
 
-pub fn run() {
-    let part1_file = "src/day1/input.txt";
+use crate::error::{parse_number, ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
 
-    let contents = fs::read_to_string(part1_file).expect("Something went wrong reading the file");
+/// Sonar Sweep: count how often the sea floor depth increases.
+pub struct Day1;
 
-    let lines1 = contents.lines();
-    println!("part 1: {}", part1(lines1));
+impl Solution for Day1 {
+    const DAY: u8 = 1;
 
-    let part2_file = "src/day1/input.txt";
-    let contents = fs::read_to_string(part2_file).expect("Something went wrong reading the file");
+    type Input = Vec<u32>;
 
-    let lines2 = contents.lines();
-    println!("part 2: {}", part2(lines2));
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        parse_depths(input.lines())
+    }
+
+    fn part1(&self, depths: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(depths).into())
+    }
+
+    fn part2(&self, depths: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(depths).into())
+    }
+}
+
+/// One depth per line.
+pub fn parse_depths(lines: Lines) -> Result<Vec<u32>, ParseError> {
+    lines
+        .enumerate()
+        .map(|(i, line)| parse_number(i + 1, Span::line(line).trim()))
+        .collect()
+}
+
+/// How many depths are deeper than the one before.
+pub fn part1(depths: &[u32]) -> u64 {
+    count_windows(depths.iter().copied(), 1, Comparison::Increase)
+}
+
+/// How many three depth sliding windows sum deeper than the window before.
+pub fn part2(depths: &[u32]) -> u64 {
+    count_windows(depths.iter().copied(), 3, Comparison::Increase)
+}
+
+/// How a window's sum compares to the sum of the window before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Increase,
+    Decrease,
+    Plateau,
+}
+
+impl Comparison {
+    pub fn matches(&self, previous: u32, current: u32) -> bool {
+        match self {
+            Comparison::Increase => current > previous,
+            Comparison::Decrease => current < previous,
+            Comparison::Plateau => current == previous,
+        }
+    }
+}
+
+/// Counts sliding windows of `size` depths whose sum compares to the previous window's sum by
+/// `comparison`.
+///
+/// Neighbouring windows share all but one depth, so comparing their sums is the same as comparing
+/// the depth entering the window with the one leaving it. The last `size` depths are kept in a ring
+/// buffer to do that in O(n) time, without summing and so without overflow.
+#[derive(Debug, Clone)]
+pub struct SlidingWindows {
+    comparison: Comparison,
+    window: Vec<u32>,
+    size: usize,
+    next: usize,
+    count: u64,
+}
+
+impl SlidingWindows {
+    /// Panics if `size` is 0.
+    pub fn new(size: usize, comparison: Comparison) -> SlidingWindows {
+        assert!(size > 0, "windows must hold at least one depth");
+        SlidingWindows {
+            comparison,
+            window: Vec::with_capacity(size),
+            size,
+            next: 0,
+            count: 0,
+        }
+    }
+
+    pub fn push(&mut self, depth: u32) {
+        if self.window.len() < self.size {
+            self.window.push(depth);
+            return;
+        }
+
+        let leaving = self.window[self.next];
+        if self.comparison.matches(leaving, depth) {
+            self.count += 1;
+        }
+        self.window[self.next] = depth;
+        self.next = (self.next + 1) % self.size;
+    }
+
+    /// How many windows so far matched the comparison.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// Counts windows of `size` depths that compare to the window before by `comparison`.
+pub fn count_windows<I: IntoIterator<Item = u32>>(
+    depths: I,
+    size: usize,
+    comparison: Comparison,
+) -> u64 {
+    let mut windows = SlidingWindows::new(size, comparison);
+    for depth in depths {
+        windows.push(depth);
+    }
+    windows.count()
+}
+
+/// Why a depth stream stopped early.
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{}", e),
+            StreamError::Parse(e) => write!(f, "{}", e),
+        }
+    }
 }
 
-fn part1(mut lines: Lines) -> u32 {
-    let mut prev = lines.next().unwrap().parse::<u32>().unwrap();
+impl Error for StreamError {}
 
-    let mut gt_count = 0;
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> StreamError {
+        StreamError::Io(e)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(e: ParseError) -> StreamError {
+        StreamError::Parse(e)
+    }
+}
+
+/// The running count after each depth read from a stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub line: usize,
+    pub depth: u32,
+    pub count: u64,
+}
+
+/// Reads one depth per line from `reader` and counts windows as it goes, yielding the count after
+/// every depth. Only the current line and one window of depths are held in memory, so the input can
+/// be as long as it likes, or never end.
+///
+/// The stream stops after the first error.
+pub struct DepthStream<R> {
+    reader: R,
+    buffer: String,
+    line: usize,
+    windows: SlidingWindows,
+    failed: bool,
+}
+
+impl<R: BufRead> DepthStream<R> {
+    pub fn new(reader: R, size: usize, comparison: Comparison) -> DepthStream<R> {
+        DepthStream {
+            reader,
+            buffer: String::new(),
+            line: 0,
+            windows: SlidingWindows::new(size, comparison),
+            failed: false,
+        }
+    }
 
-    loop {
-        let next = lines.next();
-        if next.is_none() {
-            break;
+    /// The count so far. Named so it isn't shadowed by [`Iterator::count`].
+    pub fn total(&self) -> u64 {
+        self.windows.count()
+    }
+
+    fn read_depth(&mut self) -> Result<Option<u32>, StreamError> {
+        self.buffer.clear();
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            return Ok(None);
         }
-        let num = next.unwrap().parse::<u32>().unwrap();
-        if num > prev {
-            gt_count += 1;
+        self.line += 1;
+
+        let text = self.buffer.trim_end_matches(&['\n', '\r'][..]);
+        Ok(Some(parse_number(self.line, Span::line(text).trim())?))
+    }
+}
+
+impl<R: BufRead> Iterator for DepthStream<R> {
+    type Item = Result<Progress, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
         }
-        prev = num;
+
+        match self.read_depth() {
+            Ok(Some(depth)) => {
+                self.windows.push(depth);
+                Some(Ok(Progress {
+                    line: self.line,
+                    depth,
+                    count: self.windows.count(),
+                }))
+            }
+            Ok(None) => None,
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Counts windows over a whole stream of depths in constant memory.
+pub fn count_stream<R: BufRead>(
+    reader: R,
+    size: usize,
+    comparison: Comparison,
+) -> Result<u64, StreamError> {
+    let mut stream = DepthStream::new(reader, size, comparison);
+    for progress in &mut stream {
+        progress?;
+    }
+    Ok(stream.total())
+}
+
+/// A stretch of consecutive increases or decreases, from `start_line` to `end_line` inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Run {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl Run {
+    /// How many steps the run is made of.
+    pub fn len(&self) -> usize {
+        self.end_line - self.start_line
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The change from one reading to the reading on `line`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jump {
+    pub line: usize,
+    pub from: u32,
+    pub to: u32,
+}
+
+impl Jump {
+    pub fn change(&self) -> i64 {
+        self.to as i64 - self.from as i64
+    }
+}
+
+/// A reading more than `k` standard deviations from the readings in the window before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anomaly {
+    pub line: usize,
+    pub depth: u32,
+    pub window_mean: f64,
+    pub window_std_dev: f64,
+}
+
+impl Anomaly {
+    /// How many standard deviations the reading is from the window's mean, or `None` if the
+    /// window was flat: with no spread, any change at all is an anomaly but has no size.
+    pub fn deviations(&self) -> Option<f64> {
+        (self.window_std_dev > 0.0)
+            .then(|| (self.depth as f64 - self.window_mean).abs() / self.window_std_dev)
     }
+}
 
-    gt_count
+/// Summary statistics of a sonar sweep. Line numbers are 1-based, matching the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthProfile {
+    pub readings: usize,
+    pub min: u32,
+    pub max: u32,
+    pub mean: f64,
+    pub longest_increase: Option<Run>,
+    pub longest_decrease: Option<Run>,
+    /// The largest change between neighbouring readings, in either direction.
+    pub largest_jump: Option<Jump>,
+    /// The mean of every full `window` of readings, the first ending on line `window`.
+    pub rolling_average: Vec<f64>,
+    pub anomalies: Vec<Anomaly>,
 }
 
-fn part2(mut lines: Lines) -> u32 {
-    let mut first = lines.next().unwrap().parse::<u32>().unwrap();
-    let mut second = lines.next().unwrap().parse::<u32>().unwrap();
-    let mut third = lines.next().unwrap().parse::<u32>().unwrap();
-    let mut gt_count = 0;
+impl DepthProfile {
+    /// Profiles `depths`, flagging readings more than `k` population standard deviations from the
+    /// `window` readings before them. `None` for an empty sweep; panics if `window` is 0.
+    pub fn new(depths: &[u32], window: usize, k: f64) -> Option<DepthProfile> {
+        assert!(window > 0, "windows must hold at least one depth");
+        let (&min, &max) = (depths.iter().min()?, depths.iter().max()?);
+        let mean = depths.iter().map(|&d| d as f64).sum::<f64>() / depths.len() as f64;
 
-    loop {
-        let next = lines.next();
-        if next.is_none() {
-            break;
+        let mut longest_increase: Option<Run> = None;
+        let mut longest_decrease: Option<Run> = None;
+        let mut largest_jump: Option<Jump> = None;
+        let mut increase_start = 1;
+        let mut decrease_start = 1;
+        for (i, pair) in depths.windows(2).enumerate() {
+            let line = i + 2;
+            if pair[1] <= pair[0] {
+                increase_start = line;
+            }
+            if pair[1] >= pair[0] {
+                decrease_start = line;
+            }
+            longest(&mut longest_increase, increase_start, line);
+            longest(&mut longest_decrease, decrease_start, line);
+
+            let jump = Jump {
+                line,
+                from: pair[0],
+                to: pair[1],
+            };
+            if largest_jump.map_or(true, |j| jump.change().abs() > j.change().abs()) {
+                largest_jump = Some(jump);
+            }
         }
-        let fourth = next.unwrap().parse::<u32>().unwrap();
-        let window1 = first + second + third;
-        let window2 = second + third + fourth;
-        if window2 > window1 {
-            gt_count += 1;
+
+        // A running sum and Welford's sum of squared deviations from the mean over the window,
+        // so each step is O(1) however wide the window is. Summing squares and subtracting the
+        // squared mean instead would cancel away the spread of deep readings that barely vary.
+        // Rounding still creeps into the running spread, so it's recounted from scratch each
+        // time the window turns over, and a window of equal readings is known to be flat.
+        let mut sum = 0u64;
+        let mut squared_deviations = 0.0;
+        let mut equal_run = 0;
+        let mut rolling_average = vec![];
+        let mut anomalies = vec![];
+        for (i, &depth) in depths.iter().enumerate() {
+            let mean_before = match i.min(window) {
+                0 => 0.0,
+                n => sum as f64 / n as f64,
+            };
+            if i >= window {
+                let window_mean = mean_before;
+                let window_std_dev = if equal_run >= window {
+                    0.0
+                } else {
+                    (squared_deviations / window as f64).max(0.0).sqrt()
+                };
+
+                let distance = (depth as f64 - window_mean).abs();
+                if distance > k * window_std_dev && distance > 0.0 {
+                    anomalies.push(Anomaly {
+                        line: i + 1,
+                        depth,
+                        window_mean,
+                        window_std_dev,
+                    });
+                }
+
+                let leaving = depths[i - window];
+                sum = sum - leaving as u64 + depth as u64;
+                let mean_after = sum as f64 / window as f64;
+                squared_deviations += (depth as f64 - leaving as f64)
+                    * (depth as f64 - mean_after + leaving as f64 - mean_before);
+            } else {
+                sum += depth as u64;
+                let mean_after = sum as f64 / (i + 1) as f64;
+                squared_deviations += (depth as f64 - mean_before) * (depth as f64 - mean_after);
+            }
+            if (i + 1) % window == 0 {
+                squared_deviations = squared_deviations_from_mean(&depths[i + 1 - window..=i]);
+            }
+            equal_run = if i > 0 && depths[i - 1] == depth {
+                equal_run + 1
+            } else {
+                1
+            };
+
+            if i + 1 >= window {
+                rolling_average.push(sum as f64 / window as f64);
+            }
         }
-        first = second;
-        second = third;
-        third = fourth;
+
+        Some(DepthProfile {
+            readings: depths.len(),
+            min,
+            max,
+            mean,
+            longest_increase,
+            longest_decrease,
+            largest_jump,
+            rolling_average,
+            anomalies,
+        })
+    }
+}
+
+/// The sum of squared deviations from the mean of `depths`, in two passes.
+fn squared_deviations_from_mean(depths: &[u32]) -> f64 {
+    let mean = depths.iter().map(|&d| d as f64).sum::<f64>() / depths.len() as f64;
+    depths
+        .iter()
+        .map(|&d| (d as f64 - mean) * (d as f64 - mean))
+        .sum()
+}
+
+/// Keeps the first of the longest runs seen.
+fn longest(best: &mut Option<Run>, start_line: usize, end_line: usize) {
+    let run = Run {
+        start_line,
+        end_line,
+    };
+    if !run.is_empty() && best.map_or(true, |b| run.len() > b.len()) {
+        *best = Some(run);
     }
+}
 
-    gt_count
+impl Display for DepthProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "readings: {}", self.readings)?;
+        writeln!(
+            f,
+            "depth: min {}, max {}, mean {:.2}",
+            self.min, self.max, self.mean
+        )?;
+        for (name, run) in [
+            ("longest increase", self.longest_increase),
+            ("longest decrease", self.longest_decrease),
+        ] {
+            match run {
+                Some(run) => writeln!(
+                    f,
+                    "{}: {} step{}, lines {}-{}",
+                    name,
+                    run.len(),
+                    if run.len() == 1 { "" } else { "s" },
+                    run.start_line,
+                    run.end_line
+                )?,
+                None => writeln!(f, "{}: none", name)?,
+            }
+        }
+        if let Some(jump) = self.largest_jump {
+            writeln!(
+                f,
+                "largest jump: {:+} on line {} ({} -> {})",
+                jump.change(),
+                jump.line,
+                jump.from,
+                jump.to
+            )?;
+        }
+        writeln!(f, "anomalies: {}", self.anomalies.len())?;
+        for anomaly in self.anomalies.iter() {
+            match anomaly.deviations() {
+                Some(deviations) => writeln!(
+                    f,
+                    "  line {}: {} is {:.1} standard deviations from {:.2}",
+                    anomaly.line, anomaly.depth, deviations, anomaly.window_mean
+                )?,
+                None => writeln!(
+                    f,
+                    "  line {}: {} breaks a flat window of {:.2}",
+                    anomaly.line, anomaly.depth, anomaly.window_mean
+                )?,
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
+    use std::io;
+    use std::io::{BufReader, Read};
 
     use crate::day1::*;
+    use crate::error::ParseError;
 
     #[test]
     fn part1_works() {
@@ -74,7 +504,7 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        assert_eq!(part1(lines.clone()), 7);
+        assert_eq!(part1(&parse_depths(lines).unwrap()), 7);
     }
 
     #[test]
@@ -84,6 +514,271 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        assert_eq!(part2(lines.clone()), 5);
+        assert_eq!(part2(&parse_depths(lines).unwrap()), 5);
+    }
+
+    #[test]
+    fn it_counts_windows_of_any_size() {
+        let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+        let sums = |size: usize| {
+            depths
+                .windows(size)
+                .map(|w| w.iter().sum::<u32>())
+                .collect::<Vec<u32>>()
+        };
+        for size in 1..=depths.len() + 1 {
+            for comparison in [
+                Comparison::Increase,
+                Comparison::Decrease,
+                Comparison::Plateau,
+            ] {
+                let expected = sums(size)
+                    .windows(2)
+                    .filter(|pair| comparison.matches(pair[0], pair[1]))
+                    .count() as u64;
+                assert_eq!(
+                    count_windows(depths.iter().copied(), size, comparison),
+                    expected,
+                    "size {} {:?}",
+                    size,
+                    comparison
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_counts_decreases_and_plateaus() {
+        let depths = vec![3, 3, 2, 2, 5, 1];
+        assert_eq!(count_windows(depths.clone(), 1, Comparison::Increase), 1);
+        assert_eq!(count_windows(depths.clone(), 1, Comparison::Decrease), 2);
+        assert_eq!(count_windows(depths.clone(), 1, Comparison::Plateau), 2);
+        assert_eq!(count_windows(depths, 2, Comparison::Plateau), 0);
+        assert_eq!(count_windows(vec![], 3, Comparison::Increase), 0);
+    }
+
+    #[test]
+    fn it_never_sums_the_window() {
+        let depths = vec![u32::MAX, u32::MAX, u32::MAX, u32::MAX];
+        assert_eq!(count_windows(depths, 3, Comparison::Plateau), 1);
+    }
+
+    #[test]
+    fn it_counts_past_u32() {
+        let mut windows = SlidingWindows::new(1, Comparison::Increase);
+        windows.count = u32::MAX as u64;
+        windows.push(1);
+        windows.push(2);
+        assert_eq!(windows.count(), u32::MAX as u64 + 1);
+    }
+
+    #[test]
+    fn it_streams_progress() {
+        let input: &[u8] = b"199\n200\r\n208\n200\n";
+        let progress = DepthStream::new(input, 1, Comparison::Increase)
+            .map(|p| p.unwrap())
+            .map(|p| (p.line, p.depth, p.count))
+            .collect::<Vec<(usize, u32, u64)>>();
+        assert_eq!(
+            progress,
+            vec![(1, 199, 0), (2, 200, 1), (3, 208, 2), (4, 200, 2)]
+        );
+    }
+
+    #[test]
+    fn it_streams_the_examples() {
+        let contents = fs::read_to_string("src/day1/test.txt").unwrap();
+        let reader = BufReader::new(contents.as_bytes());
+        assert_eq!(count_stream(reader, 1, Comparison::Increase).unwrap(), 7);
+        assert_eq!(
+            count_stream(contents.as_bytes(), 3, Comparison::Increase).unwrap(),
+            5
+        );
+    }
+
+    /// Produces `0\n1\n2\n...` without ever holding more than one line.
+    struct Sawtooth {
+        next: u32,
+        remaining: u32,
+        pending: Vec<u8>,
+    }
+
+    impl Read for Sawtooth {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.is_empty() {
+                if self.remaining == 0 {
+                    return Ok(0);
+                }
+                self.pending = format!("{}\n", self.next % 10).into_bytes();
+                self.next += 1;
+                self.remaining -= 1;
+            }
+            let n = buf.len().min(self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn it_streams_long_inputs() {
+        let reader = BufReader::new(Sawtooth {
+            next: 0,
+            remaining: 100_000,
+            pending: vec![],
+        });
+        // every run of 0..=9 has 9 increases
+        assert_eq!(
+            count_stream(reader, 1, Comparison::Increase).unwrap(),
+            90_000
+        );
+    }
+
+    #[test]
+    fn it_stops_streaming_at_errors() {
+        let input: &[u8] = b"199\n2o0\n208\n";
+        let mut stream = DepthStream::new(input, 1, Comparison::Increase);
+        assert!(stream.next().unwrap().is_ok());
+        match stream.next() {
+            Some(Err(StreamError::Parse(e))) => {
+                assert_eq!(e, ParseError::new(2, 1, "2o0", "expected a number"))
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn it_profiles_depths() {
+        let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        let profile = DepthProfile::new(&depths, 3, 3.0).unwrap();
+
+        assert_eq!(profile.readings, 10);
+        assert_eq!((profile.min, profile.max), (199, 269));
+        assert!((profile.mean - 225.6).abs() < 1e-9);
+        assert_eq!(
+            profile.longest_increase,
+            Some(Run {
+                start_line: 1,
+                end_line: 4
+            })
+        );
+        assert_eq!(
+            profile.longest_decrease,
+            Some(Run {
+                start_line: 4,
+                end_line: 5
+            })
+        );
+        assert_eq!(
+            profile.largest_jump,
+            Some(Jump {
+                line: 7,
+                from: 207,
+                to: 240
+            })
+        );
+
+        let expected = [607, 618, 618, 617, 647, 716, 769, 792];
+        assert_eq!(profile.rolling_average.len(), expected.len());
+        for (average, sum) in profile.rolling_average.iter().zip(expected.iter()) {
+            assert!((average - *sum as f64 / 3.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn it_flags_anomalies() {
+        let depths = [100, 102, 98, 101, 99, 100, 160, 101, 100, 30, 99];
+        let profile = DepthProfile::new(&depths, 4, 3.0).unwrap();
+
+        let lines = profile
+            .anomalies
+            .iter()
+            .map(|a| a.line)
+            .collect::<Vec<usize>>();
+        assert_eq!(lines, vec![7, 10]);
+        assert_eq!(profile.anomalies[0].depth, 160);
+        assert!(profile.anomalies[0].deviations().unwrap() > 3.0);
+
+        // a flat window has no spread, so any change at all stands out, by no measurable amount
+        let profile = DepthProfile::new(&[5, 5, 5, 6, 6], 3, 3.0).unwrap();
+        assert_eq!(profile.anomalies.len(), 1);
+        assert_eq!(profile.anomalies[0].line, 4);
+        assert_eq!(profile.anomalies[0].window_std_dev, 0.0);
+        assert_eq!(profile.anomalies[0].deviations(), None);
+        assert!(profile
+            .to_string()
+            .ends_with("anomalies: 1\n  line 4: 6 breaks a flat window of 5.00\n"));
+    }
+
+    #[test]
+    fn it_measures_the_spread_of_deep_readings() {
+        let deep = 4_000_000_000;
+        let profile = DepthProfile::new(&[deep, deep + 2, deep + 4], 2, 1.0).unwrap();
+        assert_eq!(profile.anomalies.len(), 1);
+        assert_eq!(profile.anomalies[0].window_std_dev, 1.0);
+        assert_eq!(profile.anomalies[0].deviations(), Some(3.0));
+
+        // the running spread stays true to a fresh two pass one over a long sweep
+        let depths = (0..10_000u32)
+            .map(|i| deep + i * 7919 % 13)
+            .collect::<Vec<u32>>();
+        let profile = DepthProfile::new(&depths, 5, 0.0).unwrap();
+        assert!(profile.anomalies.len() > 9000);
+        for anomaly in profile.anomalies.iter() {
+            let window = &depths[anomaly.line - 6..anomaly.line - 1];
+            let mean = window.iter().map(|&d| d as f64).sum::<f64>() / 5.0;
+            let variance = window
+                .iter()
+                .map(|&d| (d as f64 - mean) * (d as f64 - mean))
+                .sum::<f64>()
+                / 5.0;
+            assert!((anomaly.window_std_dev - variance.sqrt()).abs() < 1e-6);
+        }
+
+        // and a window that turns flat after a long varied sweep has no spread at all
+        let mut depths = (0..100_000u32)
+            .map(|i| deep + i * 7919 % 1013)
+            .collect::<Vec<u32>>();
+        depths.extend([deep + 5; 7]);
+        depths.push(deep + 6);
+        let profile = DepthProfile::new(&depths, 7, 3.0).unwrap();
+        let last = profile.anomalies.last().unwrap();
+        assert_eq!((last.line, last.window_std_dev), (100_008, 0.0));
+        assert_eq!(last.deviations(), None);
+    }
+
+    #[test]
+    fn it_profiles_short_sweeps() {
+        assert_eq!(DepthProfile::new(&[], 3, 3.0), None);
+
+        let profile = DepthProfile::new(&[7], 3, 3.0).unwrap();
+        assert_eq!(profile.longest_increase, None);
+        assert_eq!(profile.largest_jump, None);
+        assert!(profile.rolling_average.is_empty());
+        assert!(profile.anomalies.is_empty());
+    }
+
+    #[test]
+    fn it_reports_profiles() {
+        let profile = DepthProfile::new(&[10, 11, 12, 40, 12], 3, 3.0).unwrap();
+        assert_eq!(
+            profile.to_string(),
+            "readings: 5
+depth: min 10, max 40, mean 17.00
+longest increase: 3 steps, lines 1-4
+longest decrease: 1 step, lines 4-5
+largest jump: +28 on line 4 (12 -> 40)
+anomalies: 1
+  line 4: 40 is 35.5 standard deviations from 11.00
+"
+        );
+    }
+
+    #[test]
+    fn it_reports_bad_depths() {
+        let err = parse_depths("199\n200\n2o8".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(3, 1, "2o8", "expected a number"));
     }
 }
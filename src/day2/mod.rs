@@ -1,95 +1,499 @@
-use std::fs;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::str::Lines;
 
-#[derive(Debug, PartialEq)]
-enum Move {
+use crate::error::{parse_number, ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub mod plan;
+pub mod trace;
+
+/// One statement of a planned course.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Move {
     Forward(u32),
     Down(u32),
     Up(u32),
+    Back(u32),
+    /// Return to the surface at the start, with no aim.
+    Reset,
+    SetAim(i64),
+    Repeat(u32, Vec<Move>),
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Move::Forward(d) => write!(f, "forward {}", d),
+            Move::Down(d) => write!(f, "down {}", d),
+            Move::Up(d) => write!(f, "up {}", d),
+            Move::Back(d) => write!(f, "back {}", d),
+            Move::Reset => write!(f, "reset"),
+            Move::SetAim(aim) => write!(f, "set aim {}", aim),
+            Move::Repeat(count, block) => {
+                write!(f, "repeat {} {{", count)?;
+                for a_move in block {
+                    write!(f, " {};", a_move)?;
+                }
+                write!(f, " }}")
+            }
+        }
+    }
+}
+
+/// Dive!: follow the planned course and multiply the final position by the depth.
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Move>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        get_moves(input.lines())
+    }
+
+    fn part1(&self, moves: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part1(moves)?.into())
+    }
+
+    fn part2(&self, moves: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(part2(moves)?.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Word,
+    Open,
+    Close,
+    EndOfLine,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+    line: usize,
+    column: usize,
 }
 
-pub fn run() {
-    let part1_file = "src/day2/input.txt";
+impl<'a> Token<'a> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, self.column, self.text, message)
+    }
+}
+
+/// Splits a course into words and braces, ending every line with an [`Kind::EndOfLine`] token.
+/// Everything after a `#` is a comment.
+fn tokenize<'a>(lines: Lines<'a>) -> Vec<Token<'a>> {
+    let mut tokens = vec![];
+
+    for (i, line_text) in lines.enumerate() {
+        let line = i + 1;
+        let code = line_text.split('#').next().unwrap_or("");
+        let token = |kind, text: &'a str, column| Token {
+            kind,
+            text,
+            line,
+            column,
+        };
+
+        // where the current word starts, as a byte offset and a column
+        let mut word_start = None;
+        for (i, (offset, c)) in code.char_indices().enumerate() {
+            let column = i + 1;
+            if c.is_whitespace() || c == '{' || c == '}' {
+                if let Some((start, start_column)) = word_start.take() {
+                    tokens.push(token(Kind::Word, &code[start..offset], start_column));
+                }
+                if c == '{' {
+                    tokens.push(token(Kind::Open, &code[offset..offset + 1], column));
+                } else if c == '}' {
+                    tokens.push(token(Kind::Close, &code[offset..offset + 1], column));
+                }
+            } else if word_start.is_none() {
+                word_start = Some((offset, column));
+            }
+        }
+        if let Some((start, start_column)) = word_start {
+            tokens.push(token(Kind::Word, &code[start..], start_column));
+        }
 
-    let contents = fs::read_to_string(part1_file).expect("Something went wrong reading the file");
+        let end = Span::line(code.trim_end()).end();
+        tokens.push(token(Kind::EndOfLine, "", end));
+    }
 
-    let lines1 = contents.lines();
-    println!("part 1: {}", part1(lines1));
+    tokens
+}
 
-    let part2_file = "src/day2/input.txt";
-    let contents = fs::read_to_string(part2_file).expect("Something went wrong reading the file");
+/// The most repeat blocks one statement may sit inside.
+pub const MAX_NESTING: usize = 100;
 
-    let lines2 = contents.lines();
-    println!("part 2: {}", part2(lines2));
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    /// How many repeat blocks the parser is inside.
+    depth: usize,
 }
 
-fn get_moves(mut lines: Lines) -> Vec<Move> {
-    let mut moves: Vec<Move> = vec![];
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    /// Statements up to the end of the input, or up to the `}` matching `open`.
+    fn block(&mut self, open: Option<Token<'a>>) -> Result<Vec<Move>, ParseError> {
+        let mut moves = vec![];
 
-    loop {
-        let line = lines.next();
-        if line.is_none() {
-            break;
+        while let Some(token) = self.next() {
+            match token.kind {
+                Kind::EndOfLine => {}
+                Kind::Close if open.is_some() => return Ok(moves),
+                Kind::Close => return Err(token.error("unexpected '}'")),
+                Kind::Open => return Err(token.error("unexpected '{'")),
+                Kind::Word => moves.push(self.statement(token)?),
+            }
         }
-        let motion = line.unwrap();
-        let mut split = motion.split_ascii_whitespace();
 
-        let direction = split.next().unwrap();
-        let distance = split.next().unwrap();
+        match open {
+            Some(open) => Err(open.error("unclosed '{'")),
+            None => Ok(moves),
+        }
+    }
 
-        let the_move = match direction {
-            "forward" => Move::Forward(distance.parse::<u32>().unwrap()),
-            "down" => Move::Down(distance.parse::<u32>().unwrap()),
-            "up" => Move::Up(distance.parse::<u32>().unwrap()),
-            _ => panic!("reached unknown move: {}", direction),
+    fn statement(&mut self, command: Token<'a>) -> Result<Move, ParseError> {
+        let statement = match command.text {
+            "forward" => Move::Forward(self.number("expected a distance")?),
+            "down" => Move::Down(self.number("expected a distance")?),
+            "up" => Move::Up(self.number("expected a distance")?),
+            "back" => Move::Back(self.number("expected a distance")?),
+            "reset" => Move::Reset,
+            "set" => {
+                match self.next() {
+                    Some(token) if token.kind == Kind::Word && token.text == "aim" => {}
+                    Some(token) => return Err(token.error("expected 'aim'")),
+                    None => return Err(command.error("expected 'aim'")),
+                }
+                Move::SetAim(self.number("expected an aim")?)
+            }
+            "repeat" => {
+                let count = self.number("expected a repeat count")?;
+                let open = match self.next() {
+                    Some(token) if token.kind == Kind::Open => token,
+                    Some(token) => return Err(token.error("expected '{'")),
+                    None => return Err(command.error("expected '{'")),
+                };
+                if self.depth == MAX_NESTING {
+                    let message = format!("repeats nest more than {} deep", MAX_NESTING);
+                    return Err(open.error(&message));
+                }
+                self.depth += 1;
+                let block = self.block(Some(open))?;
+                self.depth -= 1;
+                Move::Repeat(count, block)
+            }
+            _ => return Err(command.error("unknown move")),
         };
 
-        moves.push(the_move);
+        match self.peek() {
+            Some(token) if token.kind == Kind::Word || token.kind == Kind::Open => {
+                Err(token.error("unexpected text after move"))
+            }
+            _ => Ok(statement),
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, message: &str) -> Result<T, ParseError> {
+        match self.peek() {
+            Some(token) if token.kind == Kind::Word => {
+                self.position += 1;
+                let span = Span {
+                    column: token.column,
+                    text: token.text,
+                };
+                parse_number(token.line, span)
+            }
+            Some(token) => Err(token.error(message)),
+            None => Err(self.end_of_input(message)),
+        }
     }
 
-    moves
+    /// An error just after the last token, for input that stops too soon.
+    fn end_of_input(&self, message: &str) -> ParseError {
+        match self.tokens.last() {
+            Some(last) => ParseError::new(
+                last.line,
+                last.column + last.text.chars().count(),
+                "",
+                message,
+            ),
+            None => ParseError::end_of_input(1, message),
+        }
+    }
 }
 
-fn part1(lines: Lines) -> u32 {
-    let moves = get_moves(lines);
-    let mut hpos = 0;
-    let mut vpos = 0;
-    for a_move in moves {
-        match a_move {
-            Move::Forward(d) => hpos += d,
-            Move::Down(d) => vpos += d,
-            Move::Up(d) => vpos -= d,
+/// Parses a planned course, one statement per line:
+///
+/// ```text
+/// forward 5      # forward, down, up and back take a distance
+/// set aim 3
+/// repeat 2 {
+///     down 1
+///     back 2
+/// }
+/// reset
+/// ```
+pub fn get_moves(lines: Lines) -> Result<Vec<Move>, ParseError> {
+    Parser {
+        tokens: tokenize(lines),
+        position: 0,
+        depth: 0,
+    }
+    .block(None)
+}
+
+/// What happens when a move would take the submarine above the surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SurfacePolicy {
+    /// Stop at the surface.
+    Clamp,
+    /// Refuse the move with [`NavigationError::Surfaced`].
+    Error,
+    /// Carry on at a negative depth.
+    AllowNegative,
+}
+
+impl Default for SurfacePolicy {
+    fn default() -> SurfacePolicy {
+        SurfacePolicy::Error
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NavigationError {
+    /// The move would have left the submarine at this (negative) depth.
+    Surfaced {
+        depth: i64,
+    },
+    Overflow,
+    /// Unrolling the repeats would take more than [`MAX_STEPS`] steps.
+    TooManySteps,
+}
+
+impl Display for NavigationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NavigationError::Surfaced { depth } => {
+                write!(f, "the submarine would surface at depth {}", depth)
+            }
+            NavigationError::Overflow => write!(f, "the submarine's position overflowed"),
+            NavigationError::TooManySteps => {
+                write!(f, "the course takes more than {} steps", MAX_STEPS)
+            }
         }
     }
+}
+
+impl Error for NavigationError {}
+
+/// A course that can't be followed has no answer.
+impl From<NavigationError> for SolveError {
+    fn from(e: NavigationError) -> SolveError {
+        SolveError::NoAnswer(e.to_string())
+    }
+}
 
-    hpos * vpos
+/// Where the submarine is and where it's pointing. Depth increases going down.
+///
+/// Every change is checked: a move that overflows, or that surfaces the submarine under
+/// [`SurfacePolicy::Error`], fails and leaves the state as it was.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Submarine {
+    pub horizontal: i64,
+    pub depth: i64,
+    pub aim: i64,
+    pub policy: SurfacePolicy,
 }
 
-fn part2(lines: Lines) -> u32 {
-    let moves = get_moves(lines);
-    let mut hpos = 0;
-    let mut vpos = 0;
-    let mut aim = 0;
+impl Submarine {
+    /// A submarine at the start, on the surface.
+    pub fn new(policy: SurfacePolicy) -> Submarine {
+        Submarine {
+            policy,
+            ..Submarine::default()
+        }
+    }
+
+    /// Moves `distance` forward, or backward if it's negative.
+    pub fn advance(&mut self, distance: i64) -> Result<(), NavigationError> {
+        self.horizontal = checked(self.horizontal.checked_add(distance))?;
+        Ok(())
+    }
+
+    /// Changes the depth by `change`, applying the surface policy.
+    pub fn dive(&mut self, change: i64) -> Result<(), NavigationError> {
+        let depth = checked(self.depth.checked_add(change))?;
+        self.depth = match self.policy {
+            _ if depth >= 0 => depth,
+            SurfacePolicy::Clamp => 0,
+            SurfacePolicy::Error => return Err(NavigationError::Surfaced { depth }),
+            SurfacePolicy::AllowNegative => depth,
+        };
+        Ok(())
+    }
+
+    /// Changes the aim by `change`.
+    pub fn turn(&mut self, change: i64) -> Result<(), NavigationError> {
+        self.aim = checked(self.aim.checked_add(change))?;
+        Ok(())
+    }
+
+    /// Moves `distance` along the aim, diving or climbing `aim * distance` on the way.
+    pub fn advance_along_aim(&mut self, distance: i64) -> Result<(), NavigationError> {
+        let change = checked(self.aim.checked_mul(distance))?;
+        let before = *self;
+        self.advance(distance)?;
+        self.dive(change).map_err(|e| {
+            *self = before;
+            e
+        })
+    }
+
+    /// Back to the start, keeping the policy.
+    pub fn reset(&mut self) {
+        *self = Submarine::new(self.policy);
+    }
+
+    /// Horizontal position times depth.
+    pub fn product(&self) -> Result<i64, NavigationError> {
+        checked(self.horizontal.checked_mul(self.depth))
+    }
+}
+
+fn checked(value: Option<i64>) -> Result<i64, NavigationError> {
+    value.ok_or(NavigationError::Overflow)
+}
+
+/// The most steps a course may take once its repeats are unrolled.
+pub const MAX_STEPS: u64 = 10_000_000;
+
+/// How many steps `moves` takes with its repeats unrolled, or `None` past [`MAX_STEPS`]. Each
+/// pass through an empty repeat block counts as a step too, so the count bounds the work
+/// of following the course.
+pub fn steps(moves: &[Move]) -> Option<u64> {
+    moves.iter().try_fold(0u64, |total, a_move| {
+        let steps = match a_move {
+            Move::Repeat(count, block) => (*count as u64).checked_mul(steps(block)?.max(1))?,
+            _ => 1,
+        };
+        Some(total + steps).filter(|&total| total <= MAX_STEPS)
+    })
+}
+
+fn follow_unrolled<N: Navigation + ?Sized>(
+    navigation: &N,
+    submarine: &mut Submarine,
+    moves: &[Move],
+) -> Result<(), NavigationError> {
     for a_move in moves {
         match a_move {
-            Move::Forward(d) => {
-                hpos += d;
-                vpos += d * aim;
+            Move::Reset => submarine.reset(),
+            Move::SetAim(aim) => submarine.aim = *aim,
+            Move::Repeat(count, block) => {
+                for _ in 0..*count {
+                    follow_unrolled(navigation, submarine, block)?;
+                }
             }
-            Move::Down(d) => aim += d,
-            Move::Up(d) => aim -= d,
+            _ => navigation.step(submarine, a_move)?,
+        }
+    }
+    Ok(())
+}
+
+/// How a submarine interprets moves. `reset`, `set aim` and `repeat` mean the same thing to
+/// every model, so a model only decides what the distance moves do.
+pub trait Navigation {
+    fn step(&self, submarine: &mut Submarine, a_move: &Move) -> Result<(), NavigationError>;
+
+    /// Follows a whole course, stopping at the first move that fails. A course of more than
+    /// [`MAX_STEPS`] steps fails with [`NavigationError::TooManySteps`] before it starts.
+    fn follow(&self, submarine: &mut Submarine, moves: &[Move]) -> Result<(), NavigationError> {
+        steps(moves).ok_or(NavigationError::TooManySteps)?;
+        follow_unrolled(self, submarine, moves)
+    }
+}
+
+/// Part 1: `down` and `up` change the depth directly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plain;
+
+impl Navigation for Plain {
+    fn step(&self, submarine: &mut Submarine, a_move: &Move) -> Result<(), NavigationError> {
+        match a_move {
+            Move::Forward(d) => submarine.advance(*d as i64),
+            Move::Back(d) => submarine.advance(-(*d as i64)),
+            Move::Down(d) => submarine.dive(*d as i64),
+            Move::Up(d) => submarine.dive(-(*d as i64)),
+            _ => self.follow(submarine, std::slice::from_ref(a_move)),
+        }
+    }
+}
+
+/// Part 2: `down` and `up` change the aim, and `forward` and `back` travel along it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aimed;
+
+impl Navigation for Aimed {
+    fn step(&self, submarine: &mut Submarine, a_move: &Move) -> Result<(), NavigationError> {
+        match a_move {
+            Move::Forward(d) => submarine.advance_along_aim(*d as i64),
+            Move::Back(d) => submarine.advance_along_aim(-(*d as i64)),
+            Move::Down(d) => submarine.turn(*d as i64),
+            Move::Up(d) => submarine.turn(-(*d as i64)),
+            _ => self.follow(submarine, std::slice::from_ref(a_move)),
         }
     }
+}
+
+/// Follows `moves` from the start with `navigation` and multiplies the final position by the depth.
+pub fn navigate<N: Navigation>(
+    navigation: N,
+    policy: SurfacePolicy,
+    moves: &[Move],
+) -> Result<i64, NavigationError> {
+    let mut submarine = Submarine::new(policy);
+    navigation.follow(&mut submarine, moves)?;
+    submarine.product()
+}
 
-    hpos * vpos
+/// Position times depth, where `down` and `up` change the depth directly.
+pub fn part1(moves: &[Move]) -> Result<i64, NavigationError> {
+    navigate(Plain, SurfacePolicy::default(), moves)
+}
+
+/// Position times depth, where `down` and `up` change the aim and `forward` dives along it.
+pub fn part2(moves: &[Move]) -> Result<i64, NavigationError> {
+    navigate(Aimed, SurfacePolicy::default(), moves)
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use crate::day2::Move::{Down, Forward, Up};
-    use crate::day2::{get_moves, part1, part2};
+    use crate::day2::Move::{Back, Down, Forward, Repeat, Reset, SetAim, Up};
+    use crate::day2::{
+        get_moves, navigate, part1, part2, steps, tokenize, Aimed, Day2, Navigation,
+        NavigationError, Parser, Plain, Submarine, SurfacePolicy, MAX_NESTING,
+    };
+    use crate::error::ParseError;
+    use crate::solution::{Answer, Solution, SolveError};
 
     #[test]
     fn it_gets_moves() {
@@ -99,7 +503,7 @@ mod tests {
 
         let lines = contents.lines();
 
-        let result = get_moves(lines.clone());
+        let result = get_moves(lines.clone()).unwrap();
         assert_eq!(result[0], Forward(5));
         assert_eq!(result[1], Down(5));
         assert_eq!(result[2], Forward(8));
@@ -108,6 +512,207 @@ mod tests {
         assert_eq!(result[5], Forward(2));
     }
 
+    #[test]
+    fn it_reports_bad_moves() {
+        let err = get_moves("forward 5\nsideways 3".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(2, 1, "sideways", "unknown move"));
+
+        let err = get_moves("down x".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 6, "x", "expected a number"));
+
+        let err = get_moves("up".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 3, "", "expected a distance"));
+
+        let err = get_moves("up 3 4".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(1, 6, "4", "unexpected text after move")
+        );
+    }
+
+    #[test]
+    fn it_parses_the_command_language() {
+        let course = "# a comment
+forward 5   # and another
+
+back 2
+set aim -3
+reset
+repeat 2 {
+    down 1
+    repeat 3 { up 1 }
+}
+repeat 4 {}";
+        assert_eq!(
+            get_moves(course.lines()).unwrap(),
+            vec![
+                Forward(5),
+                Back(2),
+                SetAim(-3),
+                Reset,
+                Repeat(2, vec![Down(1), Repeat(3, vec![Up(1)])]),
+                Repeat(4, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reports_bad_courses() {
+        let err = get_moves("repeat 2 {\n  forward 1\n".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 10, "{", "unclosed '{'"));
+
+        let err = get_moves("forward 1\n}".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(2, 1, "}", "unexpected '}'"));
+
+        let err = get_moves("repeat 2 forward 1".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 10, "forward", "expected '{'"));
+
+        let err = get_moves("repeat {".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 8, "{", "expected a repeat count"));
+
+        let err = get_moves("set 4".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 5, "4", "expected 'aim'"));
+
+        let err = get_moves("set aim # later".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 8, "", "expected an aim"));
+
+        let err = get_moves("reset now".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(1, 7, "now", "unexpected text after move")
+        );
+
+        // columns count characters, and a no-break space is two bytes
+        let err = get_moves("forward\u{a0}\u{a0}x".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 10, "x", "expected a number"));
+
+        let nested = |depth| "repeat 1 {\n".repeat(depth) + &"}\n".repeat(depth);
+        assert!(get_moves(nested(MAX_NESTING).lines()).is_ok());
+        let err = get_moves(nested(200_000).lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(101, 10, "{", "repeats nest more than 100 deep")
+        );
+
+        // without its end of line, a course stops right after the last word
+        let mut tokens = tokenize("down 2\n\nforward".lines());
+        tokens.pop();
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            depth: 0,
+        };
+        assert_eq!(
+            parser.block(None),
+            Err(ParseError::new(3, 8, "", "expected a distance"))
+        );
+    }
+
+    #[test]
+    fn both_interpreters_run_the_language() {
+        let moves =
+            get_moves("forward 10\nreset\nrepeat 3 {\n  forward 2\n  down 1\n}\nback 1".lines())
+                .unwrap();
+
+        let mut submarine = Submarine::default();
+        Plain.follow(&mut submarine, &moves).unwrap();
+        assert_eq!((submarine.horizontal, submarine.depth), (5, 3));
+
+        let mut submarine = Submarine::default();
+        Aimed.follow(&mut submarine, &moves).unwrap();
+        // aim 0, 1, 2 for the forwards, then backing up 1 at aim 3
+        assert_eq!(
+            (submarine.horizontal, submarine.depth, submarine.aim),
+            (5, 3, 3)
+        );
+
+        let moves = get_moves("set aim 2\nforward 3\nup 5\nforward 1".lines()).unwrap();
+        assert_eq!(
+            navigate(Plain, SurfacePolicy::AllowNegative, &moves),
+            Ok(4 * -5)
+        );
+        assert_eq!(part2(&moves), Ok(4 * (6 - 3)));
+    }
+
+    #[test]
+    fn the_surface_policy_decides_what_surfacing_does() {
+        let moves = get_moves("forward 2\ndown 3\nup 5\ndown 1".lines()).unwrap();
+
+        assert_eq!(navigate(Plain, SurfacePolicy::Clamp, &moves), Ok(2));
+        assert_eq!(
+            navigate(Plain, SurfacePolicy::AllowNegative, &moves),
+            Ok(-2)
+        );
+        assert_eq!(part1(&moves), Err(NavigationError::Surfaced { depth: -2 }));
+
+        // a refused move leaves the submarine where it was
+        let mut submarine = Submarine::new(SurfacePolicy::Error);
+        Aimed
+            .follow(
+                &mut submarine,
+                &get_moves("up 1\nforward 3".lines()).unwrap(),
+            )
+            .unwrap_err();
+        assert_eq!(
+            (submarine.horizontal, submarine.depth, submarine.aim),
+            (0, 0, -1)
+        );
+    }
+
+    #[test]
+    fn it_checks_for_overflow() {
+        let moves = get_moves("set aim 9223372036854775807\nforward 2".lines()).unwrap();
+        assert_eq!(part2(&moves), Err(NavigationError::Overflow));
+
+        let moves = get_moves("repeat 2 { repeat 2 { down 4294967295 } }".lines()).unwrap();
+        let mut submarine = Submarine {
+            depth: i64::MAX - 1,
+            ..Submarine::default()
+        };
+        assert_eq!(
+            Plain.follow(&mut submarine, &moves),
+            Err(NavigationError::Overflow)
+        );
+        assert_eq!(submarine.depth, i64::MAX - 1);
+    }
+
+    #[test]
+    fn huge_repeats_fail_before_they_start() {
+        let moves = get_moves("repeat 1000 { forward 1\nrepeat 9000 { back 1 } }".lines()).unwrap();
+        assert_eq!(steps(&moves), Some(9_001_000));
+        let moves = get_moves(
+            "repeat 4294967295 { repeat 4294967295 { forward 1\nback 1 } }\nforward 1".lines(),
+        )
+        .unwrap();
+        assert_eq!(steps(&moves), None);
+        let mut submarine = Submarine::default();
+        assert_eq!(
+            Plain.follow(&mut submarine, &moves),
+            Err(NavigationError::TooManySteps)
+        );
+        assert_eq!(submarine, Submarine::default());
+
+        let moves = get_moves("repeat 4294967295 { repeat 4294967295 { } }".lines()).unwrap();
+        assert_eq!(
+            Day2.part2(&moves),
+            Err(SolveError::NoAnswer(
+                "the course takes more than 10000000 steps".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn courses_that_surface_have_no_answer() {
+        let moves = Day2.parse("forward 2\ndown 1\nup 4").unwrap();
+        assert_eq!(
+            Day2.part1(&moves),
+            Err(SolveError::NoAnswer(
+                "the submarine would surface at depth -3".to_string()
+            ))
+        );
+        assert_eq!(Day2.part2(&moves), Ok(Answer::Signed(0)));
+    }
+
     #[test]
     fn part1_works() {
         let filename = "src/day2/test.txt";
@@ -115,7 +720,7 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        assert_eq!(part1(lines.clone()), 150);
+        assert_eq!(part1(&get_moves(lines).unwrap()), Ok(150));
     }
 
     #[test]
@@ -125,6 +730,6 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        assert_eq!(part2(lines.clone()), 900);
+        assert_eq!(part2(&get_moves(lines).unwrap()), Ok(900));
     }
 }
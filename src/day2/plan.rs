@@ -0,0 +1,388 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::day2::{Aimed, Move, Plain};
+
+/// Bounds on the plans a [`Planner`] may produce.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// The largest distance a single move may have.
+    pub max_step: u32,
+    /// The most distance all the moves together may cover, if limited.
+    pub max_distance: Option<u64>,
+    /// Give up on plans longer than this.
+    pub max_moves: usize,
+}
+
+impl Default for Limits {
+    /// Moves of 1 to 9, like the puzzle inputs, in plans of up to 12 moves.
+    fn default() -> Limits {
+        Limits {
+            max_step: 9,
+            max_distance: None,
+            max_moves: 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanError {
+    /// No course within the limits' step and distance bounds can reach the target.
+    Unreachable,
+    /// There may be a course, but it needs more than this many moves.
+    TooManyMoves(usize),
+}
+
+impl Display for PlanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanError::Unreachable => write!(f, "the target can't be reached"),
+            PlanError::TooManyMoves(max) => {
+                write!(f, "the target can't be reached in {} moves or fewer", max)
+            }
+        }
+    }
+}
+
+impl Error for PlanError {}
+
+/// Works backwards from where a course should end to a shortest course that gets there.
+///
+/// Plans only use `forward`, `down` and `up`, never surface the submarine, and start from the
+/// surface with no aim, so they run unchanged under every [`crate::day2::SurfacePolicy`].
+pub trait Planner {
+    fn plan(&self, horizontal: i64, depth: i64, limits: &Limits) -> Result<Vec<Move>, PlanError>;
+}
+
+/// `forward` and `down` commute, so the shortest course is as few full steps of each as will do.
+impl Planner for Plain {
+    fn plan(&self, horizontal: i64, depth: i64, limits: &Limits) -> Result<Vec<Move>, PlanError> {
+        if horizontal < 0 || depth < 0 || !within_distance(limits, horizontal as u64 + depth as u64)
+        {
+            return Err(PlanError::Unreachable);
+        }
+        if limits.max_step == 0 {
+            return if horizontal == 0 && depth == 0 {
+                Ok(vec![])
+            } else {
+                Err(PlanError::Unreachable)
+            };
+        }
+
+        // counted before building, as a far target would need more moves than fit in memory
+        let step = limits.max_step as i64;
+        let count = div_ceil(horizontal, step) as u64 + div_ceil(depth, step) as u64;
+        if count > limits.max_moves as u64 {
+            return Err(PlanError::TooManyMoves(limits.max_moves));
+        }
+
+        let mut moves = steps(horizontal, limits.max_step, Move::Forward);
+        moves.extend(steps(depth, limits.max_step, Move::Down));
+        Ok(moves)
+    }
+}
+
+/// Searches courses of one more move at a time (iterative deepening), so the first course
+/// found is a shortest one.
+impl Planner for Aimed {
+    fn plan(&self, horizontal: i64, depth: i64, limits: &Limits) -> Result<Vec<Move>, PlanError> {
+        if horizontal < 0
+            || depth < 0
+            || (horizontal == 0 && depth != 0)
+            || limits.max_step == 0 && (horizontal, depth) != (0, 0)
+            || !within_distance(limits, horizontal as u64)
+        {
+            return Err(PlanError::Unreachable);
+        }
+
+        let mut search = Search {
+            target: (horizontal, depth),
+            limits: *limits,
+            moves: vec![],
+        };
+        for length in 0..=limits.max_moves {
+            if search.extend(State::default(), length) {
+                return Ok(search.moves);
+            }
+        }
+        Err(PlanError::TooManyMoves(limits.max_moves))
+    }
+}
+
+fn within_distance(limits: &Limits, distance: u64) -> bool {
+    limits.max_distance.map_or(true, |max| distance <= max)
+}
+
+/// `total` as full `max_step` moves and then the remainder.
+fn steps(total: i64, max_step: u32, make: fn(u32) -> Move) -> Vec<Move> {
+    let max_step = max_step as i64;
+    let mut moves = vec![make(max_step as u32); (total / max_step) as usize];
+    if total % max_step != 0 {
+        moves.push(make((total % max_step) as u32));
+    }
+    moves
+}
+
+/// `n / d` rounded up, for a positive `d`, without overflowing near `i64::MAX`.
+fn div_ceil(n: i64, d: i64) -> i64 {
+    n / d + (n % d > 0) as i64
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct State {
+    horizontal: i64,
+    depth: i64,
+    aim: i64,
+    distance: u64,
+}
+
+struct Search {
+    target: (i64, i64),
+    limits: Limits,
+    moves: Vec<Move>,
+}
+
+impl Search {
+    /// Tries to finish the course from `state` in exactly `remaining` more moves.
+    fn extend(&mut self, state: State, remaining: usize) -> bool {
+        let (target_horizontal, target_depth) = self.target;
+        let step = self.limits.max_step as i64;
+        let horizontal_left = target_horizontal - state.horizontal;
+        let depth_left = target_depth - state.depth;
+
+        if remaining == 0 {
+            return horizontal_left == 0 && depth_left == 0;
+        }
+
+        // the forwards still needed, plus a turn if the current aim can't finish the dive
+        let forwards = div_ceil(horizontal_left, step);
+        let turns = if state.aim.checked_mul(horizontal_left) == Some(depth_left) {
+            0
+        } else {
+            1
+        };
+        if horizontal_left == 0 || forwards + turns > remaining as i64 {
+            return false;
+        }
+        // a bound too big to compute can't rule anything out
+        let most_aim = step
+            .checked_mul(remaining as i64 - forwards)
+            .and_then(|turning| turning.checked_add(state.aim.abs()));
+        if let Some(most_depth) = most_aim.and_then(|aim| aim.checked_mul(horizontal_left)) {
+            if depth_left.abs() > most_depth {
+                return false;
+            }
+        }
+        let distance = state
+            .distance
+            .saturating_add(horizontal_left as u64 + turns as u64);
+        if !within_distance(&self.limits, distance) {
+            return false;
+        }
+
+        // runs of the same command only matter by their total, so only the last may be short
+        let last = self.moves.last().cloned();
+        let may_follow = |candidate: fn(u32) -> Move| match &last {
+            Some(previous) => match (candidate(1), previous) {
+                (Move::Forward(_), Move::Forward(d)) => *d as i64 == step,
+                (Move::Down(_), Move::Down(d)) => *d as i64 == step,
+                (Move::Up(_), Move::Up(d)) => *d as i64 == step,
+                (Move::Down(_), Move::Up(_)) | (Move::Up(_), Move::Down(_)) => false,
+                _ => true,
+            },
+            None => true,
+        };
+
+        if may_follow(Move::Forward) {
+            for d in (1..=step.min(horizontal_left)).rev() {
+                let depth = match state
+                    .aim
+                    .checked_mul(d)
+                    .and_then(|dive| state.depth.checked_add(dive))
+                {
+                    Some(depth) if depth >= 0 => depth,
+                    _ => continue,
+                };
+                let next = State {
+                    horizontal: state.horizontal + d,
+                    depth,
+                    distance: state.distance + d as u64,
+                    ..state
+                };
+                if self.try_move(Move::Forward(d as u32), next, remaining) {
+                    return true;
+                }
+            }
+        }
+
+        for (make, sign) in [(Move::Down as fn(u32) -> Move, 1), (Move::Up, -1)] {
+            if !may_follow(make) {
+                continue;
+            }
+            for d in (1..=step).rev() {
+                let next = State {
+                    aim: state.aim + sign * d,
+                    distance: state.distance + d as u64,
+                    ..state
+                };
+                if self.try_move(make(d as u32), next, remaining) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn try_move(&mut self, a_move: Move, next: State, remaining: usize) -> bool {
+        self.moves.push(a_move);
+        if self.extend(next, remaining - 1) {
+            return true;
+        }
+        self.moves.pop();
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::plan::{Limits, PlanError, Planner};
+    use crate::day2::Move::{Down, Forward};
+    use crate::day2::{Aimed, Move, Navigation, Plain, Submarine, SurfacePolicy};
+
+    fn follow<N: Navigation>(navigation: N, moves: &[Move]) -> (i64, i64) {
+        let mut submarine = Submarine::new(SurfacePolicy::Error);
+        navigation.follow(&mut submarine, moves).unwrap();
+        (submarine.horizontal, submarine.depth)
+    }
+
+    #[test]
+    fn it_plans_plain_courses() {
+        let limits = Limits::default();
+        assert_eq!(
+            Plain.plan(15, 10, &limits).unwrap(),
+            vec![Forward(9), Forward(6), Down(9), Down(1)]
+        );
+        assert_eq!(Plain.plan(0, 0, &limits).unwrap(), vec![]);
+        assert_eq!(Plain.plan(-1, 0, &limits), Err(PlanError::Unreachable));
+
+        let short = Limits {
+            max_moves: 3,
+            ..limits
+        };
+        assert_eq!(Plain.plan(15, 10, &short), Err(PlanError::TooManyMoves(3)));
+        let near = Limits {
+            max_distance: Some(24),
+            ..limits
+        };
+        assert_eq!(Plain.plan(15, 10, &near), Err(PlanError::Unreachable));
+    }
+
+    #[test]
+    fn far_targets_dont_overflow() {
+        let limits = Limits::default();
+        assert_eq!(
+            Plain.plan(i64::MAX, 0, &limits),
+            Err(PlanError::TooManyMoves(12))
+        );
+        assert_eq!(
+            Plain.plan(i64::MAX, i64::MAX, &limits),
+            Err(PlanError::TooManyMoves(12))
+        );
+        let near = Limits {
+            max_distance: Some(100),
+            ..limits
+        };
+        assert_eq!(
+            Plain.plan(i64::MAX, i64::MAX, &near),
+            Err(PlanError::Unreachable)
+        );
+
+        let long_steps = Limits {
+            max_step: u32::MAX,
+            max_distance: None,
+            max_moves: 2,
+        };
+        assert_eq!(
+            Plain.plan(u32::MAX as i64 + 1, u32::MAX as i64, &long_steps),
+            Err(PlanError::TooManyMoves(2))
+        );
+        assert_eq!(
+            Plain
+                .plan(u32::MAX as i64, u32::MAX as i64, &long_steps)
+                .map(|m| m.len()),
+            Ok(2)
+        );
+
+        assert_eq!(
+            Aimed.plan(i64::MAX, i64::MAX, &limits),
+            Err(PlanError::TooManyMoves(12))
+        );
+        assert_eq!(
+            Aimed.plan(i64::MAX, 1, &long_steps),
+            Err(PlanError::TooManyMoves(2))
+        );
+    }
+
+    #[test]
+    fn it_plans_shortest_aimed_courses() {
+        let limits = Limits::default();
+
+        // the example course takes 6 moves to get here
+        let moves = Aimed.plan(15, 60, &limits).unwrap();
+        assert_eq!(moves.len(), 3);
+        assert_eq!(follow(Aimed, &moves), (15, 60));
+
+        // a single turn can't make 5 from 3 forwards, but a turn after the first forward can
+        let moves = Aimed.plan(3, 5, &limits).unwrap();
+        assert_eq!(moves, vec![Forward(2), Down(5), Forward(1)]);
+
+        assert_eq!(Aimed.plan(0, 4, &limits), Err(PlanError::Unreachable));
+        assert_eq!(
+            Aimed.plan(200, 1, &limits),
+            Err(PlanError::TooManyMoves(12))
+        );
+    }
+
+    #[test]
+    fn aimed_plans_respect_the_distance_limit() {
+        let limits = Limits {
+            max_step: 3,
+            max_distance: Some(8),
+            max_moves: 8,
+        };
+        let moves = Aimed.plan(4, 6, &limits).unwrap();
+        let distance = moves
+            .iter()
+            .map(|m| match m {
+                Move::Forward(d) | Move::Down(d) | Move::Up(d) => *d,
+                _ => panic!("unexpected {:?}", m),
+            })
+            .sum::<u32>();
+        assert!(distance <= 8);
+        assert_eq!(follow(Aimed, &moves), (4, 6));
+    }
+
+    #[test]
+    fn plans_round_trip_through_the_interpreters() {
+        let limits = Limits {
+            max_step: 4,
+            max_distance: None,
+            max_moves: 8,
+        };
+        for horizontal in 0..=8 {
+            for depth in 0..=12 {
+                let plain = Plain.plan(horizontal, depth, &limits).unwrap();
+                assert_eq!(follow(Plain, &plain), (horizontal, depth));
+
+                match Aimed.plan(horizontal, depth, &limits) {
+                    Ok(aimed) => {
+                        assert_eq!(follow(Aimed, &aimed), (horizontal, depth));
+                        assert!(aimed.len() <= plain.len() + 1);
+                    }
+                    Err(e) => assert_eq!((horizontal, e), (0, PlanError::Unreachable)),
+                }
+            }
+        }
+    }
+}
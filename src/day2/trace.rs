@@ -0,0 +1,289 @@
+use std::fmt::Write;
+
+use crate::day2::{steps, Move, Navigation, NavigationError, Submarine, SurfacePolicy};
+
+/// The submarine after one move of a course. Repeat blocks are unrolled, so `command` is
+/// never a [`Move::Repeat`] and `step` counts every move actually made, from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub step: usize,
+    pub command: Move,
+    pub horizontal: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+struct Frame<'a> {
+    moves: &'a [Move],
+    next: usize,
+    remaining: u32,
+}
+
+/// Follows a course one move at a time, yielding a [`Snapshot`] after each. Stops after the
+/// first move that fails, and a course of more than [`MAX_STEPS`](crate::day2::MAX_STEPS)
+/// steps fails with [`NavigationError::TooManySteps`] before the first move.
+pub struct Trace<'a, N: Navigation> {
+    navigation: N,
+    submarine: Submarine,
+    stack: Vec<Frame<'a>>,
+    step: usize,
+    refused: Option<NavigationError>,
+}
+
+/// Traces `moves` from the start with `navigation`.
+pub fn trace<N: Navigation>(navigation: N, policy: SurfacePolicy, moves: &[Move]) -> Trace<N> {
+    let refused = steps(moves)
+        .is_none()
+        .then(|| NavigationError::TooManySteps);
+    Trace {
+        navigation,
+        submarine: Submarine::new(policy),
+        stack: match refused {
+            Some(_) => vec![],
+            None => vec![Frame {
+                moves,
+                next: 0,
+                remaining: 1,
+            }],
+        },
+        step: 0,
+        refused,
+    }
+}
+
+impl<'a, N: Navigation> Trace<'a, N> {
+    /// The next move to make, unrolling repeat blocks on the way.
+    fn next_move(&mut self) -> Option<&'a Move> {
+        loop {
+            let frame = self.stack.last_mut()?;
+            if frame.next == frame.moves.len() {
+                frame.remaining -= 1;
+                if frame.remaining == 0 {
+                    self.stack.pop();
+                } else {
+                    frame.next = 0;
+                }
+                continue;
+            }
+
+            let a_move = &frame.moves[frame.next];
+            frame.next += 1;
+            match a_move {
+                Move::Repeat(count, block) => {
+                    if *count > 0 && !block.is_empty() {
+                        self.stack.push(Frame {
+                            moves: block,
+                            next: 0,
+                            remaining: *count,
+                        });
+                    }
+                }
+                _ => return Some(a_move),
+            }
+        }
+    }
+}
+
+impl<'a, N: Navigation> Iterator for Trace<'a, N> {
+    type Item = Result<Snapshot, NavigationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.refused.take() {
+            return Some(Err(e));
+        }
+        let a_move = self.next_move()?;
+        if let Err(e) = self.navigation.step(&mut self.submarine, a_move) {
+            self.stack.clear();
+            return Some(Err(e));
+        }
+
+        self.step += 1;
+        Some(Ok(Snapshot {
+            step: self.step,
+            command: a_move.clone(),
+            horizontal: self.submarine.horizontal,
+            depth: self.submarine.depth,
+            aim: self.submarine.aim,
+        }))
+    }
+}
+
+/// One row per snapshot under a `model,step,command,horizontal,depth,aim` header, for
+/// comparing models on the same course. The start isn't a step, so it has no row.
+pub fn to_csv(traces: &[(&str, &[Snapshot])]) -> String {
+    let mut csv = "model,step,command,horizontal,depth,aim\n".to_string();
+    for (model, snapshots) in traces {
+        for s in snapshots.iter() {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                model, s.step, s.command, s.horizontal, s.depth, s.aim
+            )
+            .unwrap();
+        }
+    }
+    csv
+}
+
+const COLOURS: [&str; 4] = ["#1f77b4", "#d62728", "#2ca02c", "#9467bd"];
+
+/// An SVG with one labelled polyline per trace, from the start through each snapshot, with
+/// depth increasing down the page. The axes are scaled independently to fill the picture,
+/// since depths under the aimed model dwarf the horizontal distances.
+pub fn to_svg(traces: &[(&str, &[Snapshot])]) -> String {
+    const WIDTH: f64 = 800.0;
+    const HEIGHT: f64 = 400.0;
+    const MARGIN: f64 = 20.0;
+
+    let points = traces
+        .iter()
+        .flat_map(|(_, snapshots)| snapshots.iter().map(|s| (s.horizontal, s.depth)))
+        .chain(std::iter::once((0, 0)));
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (0, 0, 0, 0);
+    for (x, y) in points {
+        min_x = min_x.min(x);
+        max_x = max_x.max(x);
+        min_y = min_y.min(y);
+        max_y = max_y.max(y);
+    }
+    let scale = |value: i64, min: i64, max: i64, size: f64| {
+        let span = (max - min).max(1) as f64;
+        MARGIN + (value - min) as f64 / span * (size - 2.0 * MARGIN)
+    };
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+        WIDTH, HEIGHT, WIDTH, HEIGHT
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "  <rect width=\"100%\" height=\"100%\" fill=\"white\"/>"
+    )
+    .unwrap();
+
+    for (i, (model, snapshots)) in traces.iter().enumerate() {
+        let colour = COLOURS[i % COLOURS.len()];
+        let path = std::iter::once((0, 0))
+            .chain(snapshots.iter().map(|s| (s.horizontal, s.depth)))
+            .map(|(x, y)| {
+                format!(
+                    "{:.1},{:.1}",
+                    scale(x, min_x, max_x, WIDTH),
+                    scale(y, min_y, max_y, HEIGHT)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        writeln!(
+            svg,
+            "  <polyline fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" points=\"{}\"/>",
+            colour, path
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "  <text x=\"{}\" y=\"{}\" fill=\"{}\" font-family=\"sans-serif\" font-size=\"12\">{}</text>",
+            MARGIN,
+            HEIGHT - MARGIN / 2.0 - 14.0 * (traces.len() - 1 - i) as f64,
+            colour,
+            model
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day2::trace::{to_csv, to_svg, trace, Snapshot};
+    use crate::day2::Move::{Down, Forward, Up};
+    use crate::day2::{get_moves, Aimed, NavigationError, Plain, SurfacePolicy};
+
+    fn snapshots(moves: &str) -> (Vec<Snapshot>, Vec<Snapshot>) {
+        let moves = get_moves(moves.lines()).unwrap();
+        (
+            trace(Plain, SurfacePolicy::default(), &moves)
+                .collect::<Result<_, _>>()
+                .unwrap(),
+            trace(Aimed, SurfacePolicy::default(), &moves)
+                .collect::<Result<_, _>>()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn it_traces_each_step() {
+        let (plain, aimed) = snapshots("forward 5\nrepeat 2 {\n  down 2\n  forward 1\n}\nup 1");
+
+        assert_eq!(plain.len(), 6);
+        assert_eq!(
+            plain[3],
+            Snapshot {
+                step: 4,
+                command: Down(2),
+                horizontal: 6,
+                depth: 4,
+                aim: 0,
+            }
+        );
+        assert_eq!(
+            aimed.iter().map(|s| &s.command).collect::<Vec<_>>(),
+            vec![
+                &Forward(5),
+                &Down(2),
+                &Forward(1),
+                &Down(2),
+                &Forward(1),
+                &Up(1)
+            ]
+        );
+        assert_eq!(
+            aimed.last().map(|s| (s.horizontal, s.depth, s.aim)),
+            Some((7, 6, 3))
+        );
+    }
+
+    #[test]
+    fn it_stops_at_the_first_failed_step() {
+        let moves = get_moves("forward 1\nup 2\nforward 1".lines()).unwrap();
+        let steps = trace(Plain, SurfacePolicy::Error, &moves).collect::<Vec<_>>();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1], Err(NavigationError::Surfaced { depth: -2 }));
+
+        let moves =
+            get_moves("repeat 4294967295 { repeat 4294967295 { forward 1 } }".lines()).unwrap();
+        let steps = trace(Aimed, SurfacePolicy::Error, &moves).collect::<Vec<_>>();
+        assert_eq!(steps, vec![Err(NavigationError::TooManySteps)]);
+    }
+
+    #[test]
+    fn it_exports_csv_and_svg() {
+        let (plain, aimed) = snapshots("forward 5\ndown 5\nforward 8");
+        let traces = [("plain", plain.as_slice()), ("aimed", aimed.as_slice())];
+
+        assert_eq!(
+            to_csv(&traces),
+            "model,step,command,horizontal,depth,aim
+plain,1,forward 5,5,0,0
+plain,2,down 5,5,5,0
+plain,3,forward 8,13,5,0
+aimed,1,forward 5,5,0,0
+aimed,2,down 5,5,0,5
+aimed,3,forward 8,13,40,5
+"
+        );
+
+        let svg = to_svg(&traces);
+        assert!(svg.starts_with("<svg "));
+        assert_eq!(svg.matches("<polyline ").count(), 2);
+        // both start at the top left and the aimed course ends at the bottom right
+        assert!(svg.contains("points=\"20.0,20.0 "));
+        assert!(svg.contains(" 780.0,380.0\"/>"));
+        assert!(svg.contains(">aimed</text>"));
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Mul;
+
+/// A fixed number of bits, written and indexed most significant first like a binary number.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    /// `len` zero bits.
+    pub fn zeros(len: usize) -> BitVec {
+        BitVec {
+            words: vec![0; (len + 63) / 64],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The bit in column `index`, counting from the left. Panics past the end.
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "bit {} of {}", index, self.len);
+        self.words[index / 64] >> (index % 64) & 1 == 1
+    }
+
+    /// Sets the bit in column `index`, counting from the left. Panics past the end.
+    pub fn set(&mut self, index: usize, bit: bool) {
+        assert!(index < self.len, "bit {} of {}", index, self.len);
+        let mask = 1 << (index % 64);
+        if bit {
+            self.words[index / 64] |= mask;
+        } else {
+            self.words[index / 64] &= !mask;
+        }
+    }
+
+    /// Appends a bit on the right.
+    pub fn push(&mut self, bit: bool) {
+        if self.len % 64 == 0 {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, bit);
+    }
+
+    /// The bits 64 at a time: column `i` is bit `i % 64` of word `i / 64`, and any bits past
+    /// the end are clear.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// The first column from `from` on where `self` and `other`, which must be as long, have
+    /// different bits. Compares 64 columns at a time.
+    pub fn first_difference(&self, other: &BitVec, from: usize) -> Option<usize> {
+        assert_eq!(
+            self.len, other.len,
+            "comparing bit vectors of different lengths"
+        );
+        if from >= self.len {
+            return None;
+        }
+
+        let first = from / 64;
+        self.words[first..]
+            .iter()
+            .zip(other.words[first..].iter())
+            .enumerate()
+            .find_map(|(i, (a, b))| {
+                let mut diff = a ^ b;
+                if i == 0 {
+                    diff &= !0 << (from % 64);
+                }
+                (diff != 0).then(|| (first + i) * 64 + diff.trailing_zeros() as usize)
+            })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(move |i| self.get(i))
+    }
+
+    /// Every bit flipped.
+    pub fn not(&self) -> BitVec {
+        let mut flipped = BitVec {
+            words: self.words.iter().map(|w| !w).collect(),
+            len: self.len,
+        };
+        // keep the unused bits of the last word clear so equal vectors compare equal
+        if self.len % 64 != 0 {
+            *flipped.words.last_mut().unwrap() &= (1 << (self.len % 64)) - 1;
+        }
+        flipped
+    }
+
+    /// The bits read as an unsigned binary number.
+    pub fn to_biguint(&self) -> BigUint {
+        let mut limbs = vec![0u32; (self.len + 31) / 32];
+        for (i, bit) in self.iter().enumerate() {
+            if bit {
+                let power = self.len - 1 - i;
+                limbs[power / 32] |= 1 << (power % 32);
+            }
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(bits: I) -> BitVec {
+        let mut vec = BitVec::default();
+        for bit in bits {
+            vec.push(bit);
+        }
+        vec
+    }
+}
+
+/// The bits as a string of `0`s and `1`s.
+impl Display for BitVec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for bit in self.iter() {
+            write!(f, "{}", if bit { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for BitVec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// An unsigned integer of any size, just big enough for day 3's products.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Least significant first, with no trailing zeros, so zero has no limbs.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn from_limbs(mut limbs: Vec<u32>) -> BigUint {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    /// The value if it fits in a `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.limbs.len() > 2 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |value, &limb| value << 32 | limb as u64),
+        )
+    }
+
+    /// Divides in place by a small divisor, returning the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let value = remainder << 32 | *limb as u64;
+            *limb = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        *self = BigUint::from_limbs(std::mem::take(&mut self.limbs));
+        remainder as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> BigUint {
+        BigUint::from_limbs(vec![n as u32, (n >> 32) as u32])
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut product = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let value = product[i + j] as u64 + a as u64 * b as u64 + carry;
+                product[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            product[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint::from_limbs(product)
+    }
+}
+
+/// In decimal.
+impl Display for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+
+        let mut rest = self.clone();
+        let mut chunks = vec![];
+        loop {
+            chunks.push(rest.div_rem_small(CHUNK));
+            if rest.limbs.is_empty() {
+                break;
+            }
+        }
+
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for BigUint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day3::bits::{BigUint, BitVec};
+
+    fn bits(s: &str) -> BitVec {
+        s.chars().map(|c| c == '1').collect()
+    }
+
+    #[test]
+    fn it_stores_bits_in_order() {
+        let mut vec = bits("10110");
+        assert_eq!(vec.len(), 5);
+        assert!(vec.get(0) && !vec.get(1) && vec.get(3) && !vec.get(4));
+        vec.set(1, true);
+        assert_eq!(vec.to_string(), "11110");
+        assert_eq!(vec.not().to_string(), "00001");
+        assert_eq!(BitVec::zeros(3).to_string(), "000");
+
+        let long = "10".repeat(100);
+        assert_eq!(bits(&long).to_string(), long);
+        assert_eq!(bits(&long).not().not(), bits(&long));
+    }
+
+    #[test]
+    fn it_finds_the_first_difference() {
+        assert_eq!(bits("10110").first_difference(&bits("10011"), 0), Some(2));
+        assert_eq!(bits("10110").first_difference(&bits("10011"), 3), Some(4));
+        assert_eq!(bits("10110").first_difference(&bits("10110"), 0), None);
+        assert_eq!(bits("10110").first_difference(&bits("10111"), 5), None);
+
+        let a = format!("{}1{}", "0".repeat(130), "0".repeat(69));
+        let b = format!("{}1{}1", "0".repeat(130), "0".repeat(68));
+        assert_eq!(bits(&a).first_difference(&bits(&b), 0), Some(199));
+        assert_eq!(
+            bits(&a).first_difference(&bits(&a.replace('1', "0")), 64),
+            Some(130)
+        );
+    }
+
+    #[test]
+    fn it_reads_bits_as_numbers() {
+        assert_eq!(bits("10110").to_biguint().to_u64(), Some(22));
+        assert_eq!(bits("").to_biguint().to_string(), "0");
+        assert_eq!(bits(&"1".repeat(64)).to_biguint().to_u64(), Some(u64::MAX));
+        assert_eq!(
+            bits(&format!("1{}", "0".repeat(64))).to_biguint().to_u64(),
+            None
+        );
+        assert_eq!(
+            bits(&format!("1{}", "0".repeat(100)))
+                .to_biguint()
+                .to_string(),
+            "1267650600228229401496703205376"
+        );
+    }
+
+    #[test]
+    fn it_multiplies_big_numbers() {
+        let max = BigUint::from(u64::MAX);
+        assert_eq!(
+            (&max * &max).to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!((&max * &BigUint::from(0)).to_string(), "0");
+        assert_eq!((&BigUint::from(22) * &BigUint::from(9)).to_u64(), Some(198));
+    }
+}
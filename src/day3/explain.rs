@@ -0,0 +1,384 @@
+use std::fmt::{Display, Formatter};
+
+use crate::day3::bits::BitVec;
+use crate::day3::trie::ReportTrie;
+use crate::day3::{
+    EpsilonRate, GammaRate, LifeSupportRating, PowerConsumption, RatingError, Report, RulePolicy,
+};
+use crate::json::Json;
+
+/// What the rule decided for one column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    MostCommon(bool),
+    LeastCommon(bool),
+    /// Every candidate has this bit, so none were dropped.
+    Unanimous(bool),
+    /// The policy couldn't pick a bit, which ends the analysis.
+    Tie,
+}
+
+impl Decision {
+    pub fn bit(&self) -> Option<bool> {
+        match self {
+            Decision::MostCommon(bit) | Decision::LeastCommon(bit) | Decision::Unanimous(bit) => {
+                Some(*bit)
+            }
+            Decision::Tie => None,
+        }
+    }
+}
+
+impl Display for Decision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decision::MostCommon(bit) => write!(f, "most common is {}", *bit as u8),
+            Decision::LeastCommon(bit) => write!(f, "least common is {}", *bit as u8),
+            Decision::Unanimous(bit) => write!(f, "every line has {}", *bit as u8),
+            Decision::Tie => write!(f, "tie"),
+        }
+    }
+}
+
+/// One column of an analysis. Columns are counted from 1, like [`crate::day3::TieError`]
+/// reports them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub column: usize,
+    pub candidates: usize,
+    pub ones: usize,
+    pub zeros: usize,
+    pub decision: Decision,
+    /// The report lines (from 1) this column ruled out.
+    pub dropped: Vec<usize>,
+}
+
+/// How one rate or rating was worked out, column by column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub name: &'static str,
+    pub policy: RulePolicy,
+    pub most_common: bool,
+    pub steps: Vec<Step>,
+    pub result: Result<BitVec, RatingError>,
+}
+
+impl PowerConsumption {
+    /// [`PowerConsumption::analyze`], explained: one explanation each for gamma and epsilon.
+    /// Every column counts every line, so nothing is ever dropped.
+    pub fn explain(report: &Report, policy: RulePolicy) -> Vec<Explanation> {
+        let total = report.lines.len();
+        let mut steps = vec![];
+        let power = PowerConsumption::analyze_with(report, policy, |column, ones, bit| {
+            steps.push(Step {
+                column: column + 1,
+                candidates: total,
+                ones,
+                zeros: total - ones,
+                decision: match bit {
+                    Ok(bit) => Decision::MostCommon(*bit),
+                    Err(_) => Decision::Tie,
+                },
+                dropped: vec![],
+            })
+        });
+
+        // epsilon is gamma flipped, so it takes the same steps with the other bit
+        let epsilon_steps = steps
+            .iter()
+            .map(|step| Step {
+                decision: match step.decision {
+                    Decision::MostCommon(bit) => Decision::LeastCommon(!bit),
+                    decision => decision,
+                },
+                ..step.clone()
+            })
+            .collect();
+        let (gamma, epsilon) = match power {
+            Ok(PowerConsumption(GammaRate(gamma), EpsilonRate(epsilon))) => {
+                (Ok(gamma), Ok(epsilon))
+            }
+            Err(e) => (Err(e.clone()), Err(e)),
+        };
+
+        vec![
+            Explanation {
+                name: "gamma",
+                policy,
+                most_common: true,
+                steps,
+                result: gamma,
+            },
+            Explanation {
+                name: "epsilon",
+                policy,
+                most_common: false,
+                steps: epsilon_steps,
+                result: epsilon,
+            },
+        ]
+    }
+}
+
+impl LifeSupportRating {
+    /// [`LifeSupportRating::analyze`], explained: one explanation each for the oxygen and CO2
+    /// ratings, following the lines that each column drops.
+    pub fn explain(report: &Report, policy: RulePolicy) -> Vec<Explanation> {
+        let trie = ReportTrie::new(report);
+        vec![
+            explain_rating("oxygen", &trie, policy, true),
+            explain_rating("co2", &trie, policy, false),
+        ]
+    }
+}
+
+/// Records each turn of the walk that [`ReportTrie::rating`] takes.
+fn explain_rating(
+    name: &'static str,
+    trie: &ReportTrie,
+    policy: RulePolicy,
+    most_common: bool,
+) -> Explanation {
+    let mut steps = vec![];
+    let result = trie.walk(policy, most_common, |turn| {
+        let decision = match turn.keep {
+            Err(_) => Decision::Tie,
+            Ok(bit) if turn.unanimous => Decision::Unanimous(bit),
+            Ok(bit) if most_common => Decision::MostCommon(bit),
+            Ok(bit) => Decision::LeastCommon(bit),
+        };
+        let mut dropped = turn.dropped.iter().map(|i| i + 1).collect::<Vec<usize>>();
+        dropped.sort_unstable();
+        steps.push(Step {
+            column: turn.column + 1,
+            candidates: turn.candidates,
+            ones: turn.ones,
+            zeros: turn.candidates - turn.ones,
+            decision,
+            dropped,
+        });
+    });
+
+    Explanation {
+        name,
+        policy,
+        most_common,
+        steps,
+        result,
+    }
+}
+
+impl Explanation {
+    pub fn to_json(&self) -> Json {
+        let steps = self
+            .steps
+            .iter()
+            .map(|step| {
+                Json::object(vec![
+                    ("column", (step.column as u64).into()),
+                    ("candidates", (step.candidates as u64).into()),
+                    ("ones", (step.ones as u64).into()),
+                    ("zeros", (step.zeros as u64).into()),
+                    ("decision", step.decision.to_string().into()),
+                    (
+                        "keep",
+                        step.decision
+                            .bit()
+                            .map_or(Json::Null, |bit| (bit as u64).into()),
+                    ),
+                    (
+                        "dropped",
+                        Json::Array(step.dropped.iter().map(|&l| (l as u64).into()).collect()),
+                    ),
+                ])
+            })
+            .collect();
+
+        let mut fields = vec![
+            ("name", self.name.into()),
+            ("policy", self.policy.to_string().into()),
+            (
+                "bit",
+                if self.most_common {
+                    "most common"
+                } else {
+                    "least common"
+                }
+                .into(),
+            ),
+            ("steps", Json::Array(steps)),
+        ];
+        match &self.result {
+            Ok(bits) => {
+                fields.push(("bits", bits.to_string().into()));
+                fields.push(("value", bits.to_biguint().to_string().into()));
+            }
+            Err(e) => fields.push(("error", e.to_string().into())),
+        }
+        Json::object(fields)
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}: {} bit, {}",
+            self.name,
+            if self.most_common {
+                "most common"
+            } else {
+                "least common"
+            },
+            self.policy
+        )?;
+        for step in self.steps.iter() {
+            write!(
+                f,
+                "  column {}: {} candidate{}, {} one{}, {} zero{}, {}",
+                step.column,
+                step.candidates,
+                plural(step.candidates),
+                step.ones,
+                plural(step.ones),
+                step.zeros,
+                plural(step.zeros),
+                step.decision
+            )?;
+            if !step.dropped.is_empty() {
+                let lines = step
+                    .dropped
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, ", dropped line{} {}", plural(step.dropped.len()), lines)?;
+            }
+            writeln!(f)?;
+        }
+        match &self.result {
+            Ok(bits) => writeln!(f, "  result: {} ({})", bits, bits.to_biguint()),
+            Err(e) => writeln!(f, "  error: {}", e),
+        }
+    }
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::day3::explain::Decision;
+    use crate::day3::{
+        parse_report, LifeSupportRating, PowerConsumption, RatingError, Report, RulePolicy,
+        TieError,
+    };
+
+    fn example() -> Report {
+        let contents =
+            fs::read_to_string("src/day3/test.txt").expect("Something went wrong reading the file");
+        parse_report(&contents.lines()).unwrap()
+    }
+
+    #[test]
+    fn it_explains_life_support_ratings() {
+        let explanations = LifeSupportRating::explain(&example(), RulePolicy::TieToOne);
+        assert_eq!(
+            explanations[0].to_string(),
+            "oxygen: most common bit, majority with ties to 1
+  column 1: 12 candidates, 7 ones, 5 zeros, most common is 1, dropped lines 1, 6, 7, 11, 12
+  column 2: 7 candidates, 3 ones, 4 zeros, most common is 0, dropped lines 2, 8, 10
+  column 3: 4 candidates, 3 ones, 1 zero, most common is 1, dropped line 9
+  column 4: 3 candidates, 2 ones, 1 zero, most common is 1, dropped line 5
+  column 5: 2 candidates, 1 one, 1 zero, most common is 1, dropped line 3
+  result: 10111 (23)
+"
+        );
+        assert_eq!(explanations[1].steps.len(), 3);
+        assert_eq!(
+            explanations[1].steps[2].decision,
+            Decision::LeastCommon(false)
+        );
+        assert_eq!(
+            explanations[1].result.as_ref().map(|bits| bits.to_string()),
+            Ok("01010".to_string())
+        );
+
+        let report = parse_report(&"1".lines()).unwrap();
+        let explained = PowerConsumption::explain(&report, RulePolicy::TieToZero);
+        assert_eq!(
+            explained[1].to_string(),
+            "epsilon: least common bit, majority with ties to 0
+  column 1: 1 candidate, 1 one, 0 zeros, least common is 0
+  result: 0 (0)
+"
+        );
+    }
+
+    #[test]
+    fn explanations_agree_with_the_analysis() {
+        let report = example();
+        let power = PowerConsumption::analyze(&report, RulePolicy::TieToZero).unwrap();
+        let explained = PowerConsumption::explain(&report, RulePolicy::TieToZero);
+        assert_eq!(explained[0].result, Ok(power.0 .0));
+        assert_eq!(explained[1].result, Ok(power.1 .0));
+        assert!(explained[0].steps.iter().all(|s| s.dropped.is_empty()));
+
+        let ratings = LifeSupportRating::analyze(&report, RulePolicy::Threshold(60)).unwrap();
+        let explained = LifeSupportRating::explain(&report, RulePolicy::Threshold(60));
+        assert_eq!(explained[0].result, Ok(ratings.0 .0));
+        assert_eq!(explained[1].result, Ok(ratings.1 .0));
+    }
+
+    #[test]
+    fn it_explains_ties_and_renders_json() {
+        let report = parse_report(&"10\n01".lines()).unwrap();
+        let explained = PowerConsumption::explain(&report, RulePolicy::StrictMajority);
+        assert_eq!(explained[0].steps.len(), 1);
+        assert_eq!(
+            explained[0].result,
+            Err(RatingError::Tie(TieError { column: 0 }))
+        );
+        assert_eq!(explained[1].result, explained[0].result);
+        assert_eq!(
+            explained[0].to_json().to_string(),
+            r#"{"name":"gamma","policy":"strict majority","bit":"most common","steps":[{"column":1,"candidates":2,"ones":1,"zeros":1,"decision":"tie","keep":null,"dropped":[]}],"error":"column 1 has as many ones as zeros, so neither is most common"}"#
+        );
+
+        let explained = LifeSupportRating::explain(&report, RulePolicy::StrictMajority);
+        assert_eq!(explained[0].steps[0].decision, Decision::Tie);
+        assert_eq!(
+            explained[0].result,
+            Err(RatingError::Tie(TieError { column: 0 }))
+        );
+
+        let explained = LifeSupportRating::explain(&report, RulePolicy::TieToOne);
+        assert_eq!(
+            explained[1].to_json().to_string(),
+            r#"{"name":"co2","policy":"majority with ties to 1","bit":"least common","steps":[{"column":1,"candidates":2,"ones":1,"zeros":1,"decision":"least common is 0","keep":0,"dropped":[1]}],"bits":"01","value":"1"}"#
+        );
+    }
+
+    #[test]
+    fn empty_reports_explain_their_error() {
+        let report = parse_report(&"".lines()).unwrap();
+        let explained = PowerConsumption::explain(&report, RulePolicy::TieToZero);
+        assert!(explained[0].steps.is_empty());
+        assert_eq!(explained[1].result, Err(RatingError::EmptyReport));
+
+        let explained = LifeSupportRating::explain(&report, RulePolicy::TieToOne);
+        assert!(explained[0].steps.is_empty());
+        assert_eq!(explained[0].result, Err(RatingError::EmptyReport));
+        assert_eq!(
+            explained[1].to_string(),
+            "co2: least common bit, majority with ties to 1\n  error: the report is empty\n"
+        );
+    }
+}
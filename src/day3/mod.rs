@@ -1,237 +1,522 @@
-use std::fmt::{Debug, Formatter};
-use std::fs;
-use std::io::Error;
-
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::{FromStr, Lines};
 
+use crate::day3::bits::{BigUint, BitVec};
+use crate::day3::trie::ReportTrie;
+use crate::error::{ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub mod bits;
+pub mod explain;
+pub mod trie;
+
+/// Binary Diagnostic: decode the submarine's power consumption and life support rating.
+pub struct Day3;
+
 // too low 1216568
-pub fn run() {
-    let part1_file = "src/day3/input.txt";
-
-    let contents = fs::read_to_string(part1_file).expect("Something went wrong reading the file");
-
-    let lines = contents.lines();
-    let report = parse_report(&lines);
-    println!(
-        "part 1: {}",
-        PowerConsumption::analyze(&report, 12).power_consumption()
-    );
-    println!(
-        "part 2: {}",
-        LifeSupportRating::analyze(&report, 12).life_support_rating()
-    );
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Report;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        parse_report(&input.lines())
+    }
+
+    fn part1(&self, report: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(PowerConsumption::analyze(report, RulePolicy::TieToZero)?
+            .power_consumption()
+            .into())
+    }
+
+    fn part2(&self, report: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(LifeSupportRating::analyze(report, RulePolicy::TieToOne)?
+            .life_support_rating()
+            .into())
+    }
+}
+
+/// Unsigned when it fits, otherwise the decimal digits.
+impl From<BigUint> for Answer {
+    fn from(n: BigUint) -> Answer {
+        match n.to_u64() {
+            Some(n) => Answer::Unsigned(n),
+            None => Answer::Text(n.to_string()),
+        }
+    }
 }
 
+/// One line of the diagnostic report, read as a binary number of any width.
 #[derive(Clone, PartialEq)]
-struct ReportLine(usize);
+pub struct ReportLine(pub BitVec);
+
+impl ReportLine {
+    pub fn width(&self) -> usize {
+        self.0.len()
+    }
+}
 
 impl Debug for ReportLine {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:05b}", self.0)
+        write!(f, "{}", self.0)
     }
 }
 
 impl FromStr for ReportLine {
-    type Err = Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parsed: usize = 0;
-        s.chars()
-            .rev()
-            .enumerate()
-            .map(|(_i, c)| c.to_string().parse::<usize>().unwrap())
-            .enumerate()
-            .map(|(i, c)| match c {
-                0 => 0 << i,
-                1 => 1 << i,
-                _ => panic!("Unexpected char while parsing"),
-            })
-            .for_each(|mask| {
-                parsed |= mask;
-            });
+        let mut parsed = BitVec::default();
+        for (column, c) in s.chars().enumerate() {
+            match c {
+                '0' => parsed.push(false),
+                '1' => parsed.push(true),
+                _ => {
+                    return Err(ParseError::new(
+                        1,
+                        column + 1,
+                        &c.to_string(),
+                        "expected a 0 or 1",
+                    ))
+                }
+            }
+        }
 
         Ok(ReportLine(parsed))
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct GammaRate(usize);
+/// A diagnostic report, where every line has the same number of bits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub lines: Vec<ReportLine>,
+    pub bit_width: usize,
+}
 
-#[derive(Debug, PartialEq)]
-struct EpsilonRate(usize);
+/// How to decide the most common bit in a column. The least common bit is always the other one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RulePolicy {
+    /// More than half the lines, with a tie as an error.
+    StrictMajority,
+    /// More than half the lines, with 1 on a tie.
+    TieToOne,
+    /// More than half the lines, with 0 on a tie.
+    TieToZero,
+    /// 1 if at least this percentage of lines have a 1, otherwise 0.
+    Threshold(u32),
+}
+
+impl RulePolicy {
+    /// The most common bit among `total` lines, `ones` of which have a 1.
+    pub fn most_common(&self, ones: usize, total: usize, column: usize) -> Result<bool, TieError> {
+        let zeros = total - ones;
+        match self {
+            RulePolicy::StrictMajority if ones == zeros => Err(TieError { column }),
+            RulePolicy::StrictMajority | RulePolicy::TieToZero => Ok(ones > zeros),
+            RulePolicy::TieToOne => Ok(ones >= zeros),
+            RulePolicy::Threshold(percent) => Ok(ones * 100 >= *percent as usize * total),
+        }
+    }
+}
+
+impl Display for RulePolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulePolicy::StrictMajority => write!(f, "strict majority"),
+            RulePolicy::TieToOne => write!(f, "majority with ties to 1"),
+            RulePolicy::TieToZero => write!(f, "majority with ties to 0"),
+            RulePolicy::Threshold(percent) => write!(f, "1 from {}% ones", percent),
+        }
+    }
+}
+
+/// A column split evenly under [`RulePolicy::StrictMajority`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TieError {
+    pub column: usize,
+}
+
+impl Display for TieError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "column {} has as many ones as zeros, so neither is most common",
+            self.column + 1
+        )
+    }
+}
 
-#[derive(PartialEq)]
-struct OxygenRating(usize);
+impl Error for TieError {}
+
+impl From<TieError> for SolveError {
+    fn from(e: TieError) -> SolveError {
+        SolveError::NoAnswer(e.to_string())
+    }
+}
 
-impl Debug for OxygenRating {
+/// Why an analysis found no rates or ratings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RatingError {
+    /// There are no lines to count bits in or to filter down to one.
+    EmptyReport,
+    Tie(TieError),
+}
+
+impl Display for RatingError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:b}", self.0)
+        match self {
+            RatingError::EmptyReport => write!(f, "the report is empty"),
+            RatingError::Tie(e) => write!(f, "{}", e),
+        }
     }
 }
 
+impl Error for RatingError {}
+
+impl From<TieError> for RatingError {
+    fn from(e: TieError) -> RatingError {
+        RatingError::Tie(e)
+    }
+}
+
+impl From<RatingError> for SolveError {
+    fn from(e: RatingError) -> SolveError {
+        SolveError::NoAnswer(e.to_string())
+    }
+}
+
+/// The most common bit in each position.
 #[derive(Debug, PartialEq)]
-struct Co2Rating(usize);
+pub struct GammaRate(pub BitVec);
 
+/// The least common bit in each position.
+#[derive(Debug, PartialEq)]
+pub struct EpsilonRate(pub BitVec);
+
+#[derive(Debug, PartialEq)]
+pub struct OxygenRating(pub BitVec);
+
+#[derive(Debug, PartialEq)]
+pub struct Co2Rating(pub BitVec);
+
+/// The rates as bit strings, with `.0.to_biguint()` for their values.
 #[derive(Debug)]
-struct PowerConsumption(GammaRate, EpsilonRate);
+pub struct PowerConsumption(pub GammaRate, pub EpsilonRate);
 
 impl PowerConsumption {
-    fn power_consumption(&self) -> usize {
-        self.0 .0 * self.1 .0
+    pub fn power_consumption(&self) -> BigUint {
+        &self.0 .0.to_biguint() * &self.1 .0.to_biguint()
     }
 
-    fn analyze(diagnostic_report: &[ReportLine], bit_width: u32) -> PowerConsumption {
-        let mut gamma_rate: usize = 0;
-        let mut epsilon_rate: usize = 0;
-        let total = diagnostic_report.len() as u32;
+    pub fn analyze(report: &Report, policy: RulePolicy) -> Result<PowerConsumption, RatingError> {
+        PowerConsumption::analyze_with(report, policy, |_, _, _| {})
+    }
 
-        for bit_position in 0..bit_width {
-            let ones = ones_at_position(&diagnostic_report.to_owned(), bit_position);
-            if ones > (total / 2) {
-                gamma_rate |= 0x1 << bit_position;
-            } else {
-                epsilon_rate |= 0x1 << bit_position;
-            }
+    /// [`PowerConsumption::analyze`], telling `visit` each column, how many lines have a 1 in
+    /// it and the most common bit, up to the first tie.
+    pub fn analyze_with<F: FnMut(usize, usize, &Result<bool, TieError>)>(
+        report: &Report,
+        policy: RulePolicy,
+        mut visit: F,
+    ) -> Result<PowerConsumption, RatingError> {
+        if report.lines.is_empty() {
+            return Err(RatingError::EmptyReport);
         }
+        let mut gamma_rate = BitVec::zeros(report.bit_width);
+        let total = report.lines.len();
 
-        PowerConsumption(GammaRate(gamma_rate), EpsilonRate(epsilon_rate))
+        for (column, ones) in column_counts(report).into_iter().enumerate() {
+            let bit = policy.most_common(ones, total, column);
+            visit(column, ones, &bit);
+            gamma_rate.set(column, bit?);
+        }
+
+        let epsilon_rate = gamma_rate.not();
+        Ok(PowerConsumption(
+            GammaRate(gamma_rate),
+            EpsilonRate(epsilon_rate),
+        ))
     }
 }
 
+/// The ratings as bit strings, with `.0.to_biguint()` for their values.
 #[derive(Debug)]
-struct LifeSupportRating(OxygenRating, Co2Rating);
+pub struct LifeSupportRating(pub OxygenRating, pub Co2Rating);
 
 impl LifeSupportRating {
-    fn life_support_rating(&self) -> usize {
-        self.0 .0 * self.1 .0
+    pub fn life_support_rating(&self) -> BigUint {
+        &self.0 .0.to_biguint() * &self.1 .0.to_biguint()
     }
 
-    fn analyze(diagnostic_report: &[ReportLine], bit_width: u32) -> LifeSupportRating {
-        let mut oxygen_rating = diagnostic_report.to_owned();
-        let mut co2_rating = diagnostic_report.to_owned();
-
-        for bit_position in 0..bit_width {
-            let bit = bit_width - bit_position - 1;
-            if oxygen_rating.len() == 1 && co2_rating.len() == 1 {
-                break;
-            }
+    /// Filters the report by the most and least common bits, from the most significant down,
+    /// as two walks down a [`ReportTrie`]. The puzzle's rules are [`RulePolicy::TieToOne`].
+    pub fn analyze(report: &Report, policy: RulePolicy) -> Result<LifeSupportRating, RatingError> {
+        let trie = ReportTrie::new(report);
 
-            if oxygen_rating.len() > 1 {
-                let total = oxygen_rating.len() as u32;
-                let ones = ones_at_position(&oxygen_rating, bit);
-                let zeros = total - ones;
-
-                if ones >= zeros {
-                    // keep values with 1 in position
-                    oxygen_rating = oxygen_rating
-                        .into_iter()
-                        .filter(|line| {
-                            let index = 0x1 << bit;
-                            (line.0 & index) > 0
-                        })
-                        .collect();
-                } else {
-                    // keep values with 0 in position
-                    oxygen_rating = oxygen_rating
-                        .into_iter()
-                        .filter(|line| {
-                            let index = 0x1 << bit;
-                            (line.0 & index) == 0
-                        })
-                        .collect();
-                }
-            }
+        Ok(LifeSupportRating(
+            OxygenRating(trie.rating(policy, true)?),
+            Co2Rating(trie.rating(policy, false)?),
+        ))
+    }
+}
 
-            if co2_rating.len() > 1 {
-                let total = co2_rating.len() as u32;
-                let ones = ones_at_position(&co2_rating, bit);
-                let zeros = total - ones;
-                if ones >= zeros {
-                    // keep values with 0 in position
-                    co2_rating = co2_rating
-                        .into_iter()
-                        .filter(|line| {
-                            let index = 0x1 << bit;
-                            (line.0 & index) == 0
-                        })
-                        .collect();
-                } else {
-                    // keep values with 1 in position
-                    co2_rating = co2_rating
-                        .into_iter()
-                        .filter(|line| {
-                            let index = 0x1 << bit;
-                            (line.0 & index) > 0
-                        })
-                        .collect();
-                }
-            }
+/// One binary number per line, all as wide as the first.
+pub fn parse_report(diagnostic_report: &Lines) -> Result<Report, ParseError> {
+    let mut lines = vec![];
+    let mut bit_width = None;
+
+    for (i, line) in diagnostic_report.clone().enumerate() {
+        let parsed = line.parse::<ReportLine>().map_err(|e| e.on_line(i + 1))?;
+        let width = *bit_width.get_or_insert(parsed.width());
+        if parsed.width() != width {
+            let message = format!(
+                "expected {} bits like line 1, found {}",
+                width,
+                parsed.width()
+            );
+            return Err(if parsed.width() > width {
+                ParseError::at(i + 1, Span::line(line).slice(width, line.len()), &message)
+            } else {
+                ParseError::new(i + 1, Span::line(line).end(), "", &message)
+            });
         }
-
-        LifeSupportRating(
-            OxygenRating(oxygen_rating.get(0).unwrap().0),
-            Co2Rating(co2_rating.get(0).unwrap().0),
-        )
+        lines.push(parsed);
     }
-}
 
-fn parse_report(diagnostic_report: &Lines) -> Vec<ReportLine> {
-    diagnostic_report
-        .clone()
-        .map(|line| line.parse::<ReportLine>().unwrap())
-        .collect::<Vec<ReportLine>>()
+    Ok(Report {
+        lines,
+        bit_width: bit_width.unwrap_or(0),
+    })
 }
 
-fn ones_at_position(report: &[ReportLine], bit_position: u32) -> u32 {
-    let mut ones: u32 = 0;
-    report.iter().for_each(|line| {
-        let index = 0x1 << bit_position;
-        if (line.0 & index) > 0 {
-            ones += 1;
+/// How many lines have a 1 in each column, counting every column in one pass over the report.
+///
+/// Counts are kept bit-sliced: `planes[k]` holds bit `k` of the counts of 64 columns at once, so
+/// adding a line is a ripple-carry add of its bits into the planes, 64 columns per word.
+pub fn column_counts(report: &Report) -> Vec<usize> {
+    let words = (report.bit_width + 63) / 64;
+    let mut planes = vec![Vec::<u64>::new(); words];
+
+    for line in report.lines.iter() {
+        for (word, &bits) in planes.iter_mut().zip(line.0.words()) {
+            let mut carry = bits;
+            for plane in word.iter_mut() {
+                if carry == 0 {
+                    break;
+                }
+                let overflow = *plane & carry;
+                *plane ^= carry;
+                carry = overflow;
+            }
+            if carry != 0 {
+                word.push(carry);
+            }
         }
-    });
+    }
 
-    ones
+    (0..report.bit_width)
+        .map(|column| {
+            planes[column / 64]
+                .iter()
+                .enumerate()
+                .map(|(k, plane)| ((plane >> (column % 64) & 1) as usize) << k)
+                .sum()
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
 
+    use crate::day3::bits::BitVec;
     use crate::day3::{
-        parse_report, Co2Rating, EpsilonRate, GammaRate, LifeSupportRating, OxygenRating,
-        PowerConsumption, ReportLine,
+        column_counts, parse_report, Co2Rating, Day3, EpsilonRate, GammaRate, LifeSupportRating,
+        OxygenRating, PowerConsumption, RatingError, Report, ReportLine, RulePolicy, TieError,
     };
+    use crate::error::ParseError;
+    use crate::solution::{Solution, SolveError};
+
+    fn bits(s: &str) -> BitVec {
+        s.chars().map(|c| c == '1').collect()
+    }
 
     #[test]
     fn it_parses_report_lines() {
         let line = "01010101";
         let result = line.parse::<ReportLine>();
-        assert_eq!(result.unwrap(), ReportLine(1 + 4 + 16 + 64));
+        assert_eq!(result.unwrap(), ReportLine(bits("01010101")));
+        assert_eq!(format!("{:?}", ReportLine(bits("0000101"))), "0000101");
+    }
+
+    #[test]
+    fn it_reports_bad_report_lines() {
+        let report = "0000\n0001\n0021";
+        let err = parse_report(&report.lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(3, 3, "2", "expected a 0 or 1"));
+
+        let err = parse_report(&"0000\n000110\n0000".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(2, 5, "10", "expected 4 bits like line 1, found 6")
+        );
+
+        let err = parse_report(&"0000\n0000\n011".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(3, 4, "", "expected 4 bits like line 1, found 3")
+        );
     }
 
     #[test]
     fn it_parses_reports() {
         let report = "0000\n0001\n0011\n0111\n1111";
         let lines = report.lines();
-        let parsed = parse_report(&lines);
-        assert_eq!(parsed.len(), 5);
-        assert_eq!(*parsed.get(0).unwrap(), ReportLine(0));
-        assert_eq!(*parsed.get(1).unwrap(), ReportLine(1));
-        assert_eq!(*parsed.get(2).unwrap(), ReportLine(3));
-        assert_eq!(*parsed.get(3).unwrap(), ReportLine(7));
-        assert_eq!(*parsed.get(4).unwrap(), ReportLine(15));
+        let parsed = parse_report(&lines).unwrap();
+        assert_eq!(parsed.bit_width, 4);
+        assert_eq!(
+            parsed.lines,
+            vec![
+                ReportLine(bits("0000")),
+                ReportLine(bits("0001")),
+                ReportLine(bits("0011")),
+                ReportLine(bits("0111")),
+                ReportLine(bits("1111")),
+            ]
+        );
+        assert_eq!(
+            parse_report(&"".lines()).unwrap(),
+            Report {
+                lines: vec![],
+                bit_width: 0
+            }
+        );
     }
 
     #[test]
     fn it_analyzes_power_consumption() {
         let report = "0000\n0001\n0011\n0111\n1111";
         let lines = report.lines();
-        let parsed = parse_report(&lines);
-        let result = PowerConsumption::analyze(&parsed, 4);
-        // 0011
-        assert_eq!(result.0, GammaRate(3));
-        assert_eq!(result.1, EpsilonRate(12));
+        let parsed = parse_report(&lines).unwrap();
+        let result = PowerConsumption::analyze(&parsed, RulePolicy::TieToZero).unwrap();
+        assert_eq!(result.0, GammaRate(bits("0011")));
+        assert_eq!(result.1, EpsilonRate(bits("1100")));
+        assert_eq!(result.0 .0.to_biguint().to_u64(), Some(3));
+        assert_eq!(result.1 .0.to_biguint().to_u64(), Some(12));
+    }
+
+    #[test]
+    fn it_counts_every_column_at_once() {
+        let report = parse_report(&"0000\n0001\n0011\n0111\n1111".lines()).unwrap();
+        assert_eq!(column_counts(&report), vec![1, 2, 3, 4]);
+
+        // enough lines to carry into several planes, across more than one word
+        let lines = (0..1000usize)
+            .map(|i| format!("{:070b}", i * 7919))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let report = parse_report(&lines.lines()).unwrap();
+        let expected = (0..70)
+            .map(|column| {
+                report
+                    .lines
+                    .iter()
+                    .filter(|line| line.0.get(column))
+                    .count()
+            })
+            .collect::<Vec<usize>>();
+        assert_eq!(column_counts(&report), expected);
+    }
+
+    #[test]
+    fn empty_reports_have_no_answer() {
+        let report = parse_report(&"".lines()).unwrap();
+        assert_eq!(
+            PowerConsumption::analyze(&report, RulePolicy::TieToZero).unwrap_err(),
+            RatingError::EmptyReport
+        );
+        assert_eq!(
+            LifeSupportRating::analyze(&report, RulePolicy::TieToOne).unwrap_err(),
+            RatingError::EmptyReport
+        );
+        let empty = Err(SolveError::NoAnswer("the report is empty".to_string()));
+        assert_eq!(Day3.part1(&report), empty);
+        assert_eq!(Day3.part2(&report), empty);
+    }
+
+    #[test]
+    fn it_analyzes_reports_wider_than_a_word() {
+        let report = format!(
+            "1{}\n0{}\n1{}",
+            "0".repeat(199),
+            "1".repeat(199),
+            "1".repeat(199)
+        );
+        let parsed = parse_report(&report.lines()).unwrap();
+        assert_eq!(parsed.bit_width, 200);
+
+        let power = PowerConsumption::analyze(&parsed, RulePolicy::TieToZero).unwrap();
+        assert_eq!(power.0 .0.to_string(), "1".repeat(200));
+        assert_eq!(power.1 .0.to_string(), "0".repeat(200));
+        assert_eq!(power.power_consumption().to_string(), "0");
+
+        let life_support = LifeSupportRating::analyze(&parsed, RulePolicy::TieToOne).unwrap();
+        assert_eq!(life_support.0 .0.to_string(), "1".repeat(200));
+        assert_eq!(
+            life_support.1 .0.to_string(),
+            format!("0{}", "1".repeat(199))
+        );
+        // (2^200 - 1) * (2^199 - 1)
+        assert_eq!(
+            life_support.life_support_rating().to_string(),
+            "1291124939043454294827959586001505937164852896414611756415327267863257422523007284371684164896165132413460911797120794625"
+        );
+    }
+
+    #[test]
+    fn policies_decide_ties_and_thresholds() {
+        // column 1 is tied, columns 2 and 3 have three ones out of four
+        let report = parse_report(&"110\n011\n111\n001".lines()).unwrap();
+        let gamma =
+            |policy| PowerConsumption::analyze(&report, policy).map(|power| power.0 .0.to_string());
+
+        assert_eq!(gamma(RulePolicy::TieToOne), Ok("111".to_string()));
+        assert_eq!(gamma(RulePolicy::TieToZero), Ok("011".to_string()));
+        assert_eq!(gamma(RulePolicy::Threshold(75)), Ok("011".to_string()));
+        assert_eq!(gamma(RulePolicy::Threshold(80)), Ok("000".to_string()));
+        assert_eq!(
+            gamma(RulePolicy::Threshold(50)),
+            gamma(RulePolicy::TieToOne)
+        );
+        assert_eq!(
+            gamma(RulePolicy::StrictMajority),
+            Err(RatingError::Tie(TieError { column: 0 }))
+        );
+
+        let ratings = |policy| {
+            LifeSupportRating::analyze(&report, policy)
+                .map(|rating| (rating.0 .0.to_string(), rating.1 .0.to_string()))
+        };
+        let pair = |oxygen: &str, co2: &str| Ok((oxygen.to_string(), co2.to_string()));
+
+        assert_eq!(ratings(RulePolicy::TieToOne), pair("111", "001"));
+        // ties now favour 0, so oxygen and CO2 swap
+        assert_eq!(ratings(RulePolicy::TieToZero), pair("001", "111"));
+        assert_eq!(ratings(RulePolicy::Threshold(75)), pair("001", "111"));
+        assert_eq!(
+            ratings(RulePolicy::StrictMajority),
+            Err(RatingError::Tie(TieError { column: 0 }))
+        );
+
+        // without a tie a strict majority agrees with the puzzle's rules
+        let report = parse_report(&"110\n011\n111".lines()).unwrap();
+        assert_eq!(
+            PowerConsumption::analyze(&report, RulePolicy::StrictMajority)
+                .unwrap()
+                .0,
+            GammaRate(bits("111"))
+        );
     }
 
     #[test]
@@ -241,12 +526,12 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let report = parse_report(&lines);
-        assert_eq!(report.len(), 12);
-        let result = PowerConsumption::analyze(&report, 5);
+        let report = parse_report(&lines).unwrap();
+        assert_eq!(report.lines.len(), 12);
+        let result = PowerConsumption::analyze(&report, RulePolicy::TieToZero).unwrap();
 
-        assert_eq!(result.0, GammaRate(0b10110));
-        assert_eq!(result.1, EpsilonRate(0b01001));
+        assert_eq!(result.0, GammaRate(bits("10110")));
+        assert_eq!(result.1, EpsilonRate(bits("01001")));
     }
 
     #[test]
@@ -256,12 +541,12 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let report = parse_report(&lines);
-        assert_eq!(report.len(), 12);
-        let result = LifeSupportRating::analyze(&report, 5);
+        let report = parse_report(&lines).unwrap();
+        assert_eq!(report.lines.len(), 12);
+        let result = LifeSupportRating::analyze(&report, RulePolicy::TieToOne).unwrap();
 
-        assert_eq!(result.0, OxygenRating(23));
-        assert_eq!(result.1, Co2Rating(10));
+        assert_eq!(result.0, OxygenRating(bits("10111")));
+        assert_eq!(result.1, Co2Rating(bits("01010")));
     }
 
     #[test]
@@ -271,9 +556,11 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let report = parse_report(&lines);
-        let result = PowerConsumption::analyze(&report, 5).power_consumption();
-        assert_eq!(result, 198);
+        let report = parse_report(&lines).unwrap();
+        let result = PowerConsumption::analyze(&report, RulePolicy::TieToZero)
+            .unwrap()
+            .power_consumption();
+        assert_eq!(result.to_u64(), Some(198));
     }
 
     #[test]
@@ -283,11 +570,11 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let report = parse_report(&lines);
-        assert_eq!(report.len(), 12);
-        let result = LifeSupportRating::analyze(&report, 5);
-        assert_eq!(result.0, OxygenRating(23));
-        assert_eq!(result.1, Co2Rating(10));
-        assert_eq!(result.life_support_rating(), 230);
+        let report = parse_report(&lines).unwrap();
+        assert_eq!(report.lines.len(), 12);
+        let result = LifeSupportRating::analyze(&report, RulePolicy::TieToOne).unwrap();
+        assert_eq!(result.0 .0.to_biguint().to_u64(), Some(23));
+        assert_eq!(result.1 .0.to_biguint().to_u64(), Some(10));
+        assert_eq!(result.life_support_rating().to_u64(), Some(198 + 32));
     }
 }
@@ -0,0 +1,430 @@
+use crate::day3::bits::BitVec;
+use crate::day3::{RatingError, Report, RulePolicy, TieError};
+
+/// No child: the root is never anyone's child, so its index can mean nothing.
+const NONE: usize = 0;
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    /// How many lines pass through this node.
+    count: usize,
+    /// The first column where the node's lines don't all agree, or the report's width if they
+    /// do. Every column from the parent's `depth` to this one is shared by all of them.
+    depth: usize,
+    /// One of the node's lines, to read the columns they share from.
+    line: usize,
+    /// Where the node's lines start in the trie's `order`.
+    start: usize,
+    /// The nodes for a 0 and a 1 in column `depth`.
+    children: [usize; 2],
+}
+
+/// One column of a walk down a [`ReportTrie`], while more than one line is left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turn<'t> {
+    pub column: usize,
+    /// How many lines were left coming into the column.
+    pub candidates: usize,
+    pub ones: usize,
+    /// Every line left has the same bit here, so the policy wasn't asked.
+    pub unanimous: bool,
+    /// The bit the walk followed, or why the policy couldn't pick one.
+    pub keep: Result<bool, TieError>,
+    /// Indexes of the report lines this column ruled out, in no particular order.
+    pub dropped: &'t [usize],
+}
+
+/// A diagnostic report as a binary trie, where each node counts the lines that start with the
+/// bits on the path to it.
+///
+/// A run of columns where every line below a node agrees is kept in that one node, so there
+/// are at most two nodes per line however wide the report is. The root's run is empty, so
+/// it always branches on the first column. Nodes live in one list, and each node's lines are
+/// one range of a list of line indexes, so walks never allocate.
+#[derive(Debug, Clone)]
+pub struct ReportTrie<'r> {
+    report: &'r Report,
+    nodes: Vec<Node>,
+    /// Line indexes, grouped so that every node's lines are next to each other.
+    order: Vec<usize>,
+}
+
+impl<'r> ReportTrie<'r> {
+    pub fn new(report: &'r Report) -> ReportTrie<'r> {
+        let mut trie = ReportTrie {
+            report,
+            nodes: vec![Node {
+                count: 0,
+                depth: 0,
+                line: 0,
+                start: 0,
+                children: [NONE; 2],
+            }],
+            order: vec![],
+        };
+        let leaves = (0..report.lines.len())
+            .map(|line| trie.insert(line))
+            .collect::<Vec<usize>>();
+        trie.group(&leaves);
+        trie
+    }
+
+    fn leaf(&mut self, line: usize) -> usize {
+        self.nodes.push(Node {
+            count: 1,
+            depth: self.report.bit_width,
+            line,
+            start: 0,
+            children: [NONE; 2],
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Adds a line, returning the leaf it ends at. Splitting a run adds a node above the old
+    /// one, so a leaf keeps its index and its lines as the trie grows.
+    fn insert(&mut self, line: usize) -> usize {
+        let bits = &self.report.lines[line].0;
+        let mut parent = (0, 0);
+        let mut node = 0;
+        let mut from = 0;
+        loop {
+            let Node {
+                count,
+                depth,
+                line: shared,
+                children,
+                ..
+            } = self.nodes[node];
+
+            // a difference inside the run splits it, with a new node for the columns before
+            let split = if from < depth {
+                self.report.lines[shared].0.first_difference(bits, from)
+            } else {
+                None
+            };
+            if let Some(column) = split.filter(|&column| column < depth) {
+                let bit = bits.get(column) as usize;
+                let leaf = self.leaf(line);
+                let mut above = Node {
+                    count: count + 1,
+                    depth: column,
+                    line: shared,
+                    start: 0,
+                    children: [NONE; 2],
+                };
+                above.children[1 - bit] = node;
+                above.children[bit] = leaf;
+                self.nodes.push(above);
+                self.nodes[parent.0].children[parent.1] = self.nodes.len() - 1;
+                return leaf;
+            }
+
+            self.nodes[node].count += 1;
+            if depth == self.report.bit_width {
+                return node;
+            }
+            let bit = bits.get(depth) as usize;
+            if children[bit] == NONE {
+                let leaf = self.leaf(line);
+                self.nodes[node].children[bit] = leaf;
+                return leaf;
+            }
+            parent = (node, bit);
+            node = children[bit];
+            from = depth + 1;
+        }
+    }
+
+    /// Fills in `order` from the leaf each line ended at, giving every node the range after
+    /// its 0 child's.
+    fn group(&mut self, leaves: &[usize]) {
+        let mut pending = vec![0];
+        while let Some(node) = pending.pop() {
+            let mut next = self.nodes[node].start;
+            for child in self.nodes[node].children {
+                if child != NONE {
+                    self.nodes[child].start = next;
+                    next += self.nodes[child].count;
+                    pending.push(child);
+                }
+            }
+        }
+
+        let mut next = self.nodes.iter().map(|n| n.start).collect::<Vec<usize>>();
+        self.order = vec![0; leaves.len()];
+        for (line, &leaf) in leaves.iter().enumerate() {
+            self.order[next[leaf]] = line;
+            next[leaf] += 1;
+        }
+    }
+
+    /// How many lines there are.
+    pub fn len(&self) -> usize {
+        self.nodes[0].count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The indexes of the lines below `node`.
+    fn lines(&self, node: usize) -> &[usize] {
+        let Node { start, count, .. } = self.nodes[node];
+        &self.order[start..start + count]
+    }
+
+    /// How many lines start with `prefix`, a string of `0`s and `1`s. Any other character
+    /// matches nothing.
+    pub fn count_prefix(&self, prefix: &str) -> usize {
+        let mut node = 0;
+        for (column, c) in prefix.chars().enumerate() {
+            let bit = match c {
+                '0' => false,
+                '1' => true,
+                _ => return 0,
+            };
+            let Node {
+                depth,
+                line,
+                children,
+                ..
+            } = self.nodes[node];
+            if column < depth {
+                if self.report.lines[line].0.get(column) != bit {
+                    return 0;
+                }
+            } else if column == self.report.bit_width || children[bit as usize] == NONE {
+                return 0;
+            } else {
+                node = children[bit as usize];
+            }
+        }
+        self.nodes[node].count
+    }
+
+    /// Walks from the root taking the most common bit at each column, or the least common if
+    /// not `most_common`, until one line is left. A column where every line left agrees is
+    /// followed as it is.
+    pub fn rating(&self, policy: RulePolicy, most_common: bool) -> Result<BitVec, RatingError> {
+        self.walk(policy, most_common, |_| {})
+    }
+
+    /// [`ReportTrie::rating`], telling `visit` about every column it takes while more than one
+    /// line is left, including the one with a tie.
+    pub fn walk<F: FnMut(Turn)>(
+        &self,
+        policy: RulePolicy,
+        most_common: bool,
+        mut visit: F,
+    ) -> Result<BitVec, RatingError> {
+        if self.is_empty() {
+            return Err(RatingError::EmptyReport);
+        }
+
+        let mut node = 0;
+        let mut from = 0;
+        loop {
+            let Node {
+                count,
+                depth,
+                line,
+                children,
+                ..
+            } = self.nodes[node];
+            if count > 1 {
+                for column in from..depth {
+                    let bit = self.report.lines[line].0.get(column);
+                    visit(Turn {
+                        column,
+                        candidates: count,
+                        ones: if bit { count } else { 0 },
+                        unanimous: true,
+                        keep: Ok(bit),
+                        dropped: &[],
+                    });
+                }
+            }
+            if depth == self.report.bit_width {
+                break;
+            }
+
+            // only the root can have a single child
+            let ones = match children[1] {
+                NONE => 0,
+                ones => self.nodes[ones].count,
+            };
+            let keep = match children {
+                [_, NONE] => Ok(false),
+                [NONE, _] => Ok(true),
+                _ => policy
+                    .most_common(ones, count, depth)
+                    .map(|bit| bit == most_common),
+            };
+            if count > 1 {
+                let dropped = match keep {
+                    Ok(bit) if children[!bit as usize] != NONE => {
+                        self.lines(children[!bit as usize])
+                    }
+                    _ => &[],
+                };
+                visit(Turn {
+                    column: depth,
+                    candidates: count,
+                    ones,
+                    unanimous: children.contains(&NONE),
+                    keep: keep.clone(),
+                    dropped,
+                });
+            }
+            node = children[keep? as usize];
+            from = depth + 1;
+        }
+        Ok(self.report.lines[self.nodes[node].line].0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::day3::trie::ReportTrie;
+    use crate::day3::{parse_report, RatingError, Report, RulePolicy};
+
+    fn example() -> Report {
+        let contents =
+            fs::read_to_string("src/day3/test.txt").expect("Something went wrong reading the file");
+        parse_report(&contents.lines()).unwrap()
+    }
+
+    #[test]
+    fn it_counts_prefixes() {
+        let report = example();
+        let trie = ReportTrie::new(&report);
+        assert_eq!(trie.len(), 12);
+        assert_eq!(trie.count_prefix(""), 12);
+        assert_eq!(trie.count_prefix("1"), 7);
+        assert_eq!(trie.count_prefix("10"), 4);
+        assert_eq!(trie.count_prefix("101"), 3);
+        assert_eq!(trie.count_prefix("10111"), 1);
+        assert_eq!(trie.count_prefix("101111"), 0);
+        assert_eq!(trie.count_prefix("0x"), 0);
+    }
+
+    #[test]
+    fn it_walks_to_ratings() {
+        let report = example();
+        let trie = ReportTrie::new(&report);
+        assert_eq!(
+            trie.rating(RulePolicy::TieToOne, true).unwrap().to_string(),
+            "10111"
+        );
+        assert_eq!(
+            trie.rating(RulePolicy::TieToOne, false)
+                .unwrap()
+                .to_string(),
+            "01010"
+        );
+
+        // duplicates share a path and only add to its counts
+        let report = parse_report(&"011\n011\n100".lines()).unwrap();
+        let trie = ReportTrie::new(&report);
+        assert_eq!(trie.count_prefix("011"), 2);
+        assert_eq!(
+            trie.rating(RulePolicy::TieToOne, true).unwrap().to_string(),
+            "011"
+        );
+        assert_eq!(
+            trie.rating(RulePolicy::TieToOne, false)
+                .unwrap()
+                .to_string(),
+            "100"
+        );
+    }
+
+    #[test]
+    fn walks_report_every_turn() {
+        let report = example();
+        let trie = ReportTrie::new(&report);
+        let mut turns = vec![];
+        let rating = trie.walk(RulePolicy::TieToOne, false, |turn| {
+            let mut dropped = turn.dropped.to_vec();
+            dropped.sort_unstable();
+            turns.push((turn.column, turn.candidates, turn.ones, turn.keep, dropped));
+        });
+        assert_eq!(rating.unwrap().to_string(), "01010");
+        assert_eq!(
+            turns,
+            vec![
+                (0, 12, 7, Ok(false), vec![1, 2, 3, 4, 7, 8, 9]),
+                (1, 5, 2, Ok(true), vec![0, 6, 10]),
+                (2, 2, 1, Ok(false), vec![5]),
+            ]
+        );
+
+        // duplicates agree on every column, and the single-child root is unanimous
+        let report = parse_report(&"011\n011".lines()).unwrap();
+        let trie = ReportTrie::new(&report);
+        let mut turns = vec![];
+        trie.walk(RulePolicy::StrictMajority, true, |turn| {
+            turns.push((turn.column, turn.unanimous, turn.keep))
+        })
+        .unwrap();
+        assert_eq!(
+            turns,
+            vec![
+                (0, true, Ok(false)),
+                (1, true, Ok(true)),
+                (2, true, Ok(true))
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_reports_have_no_ratings() {
+        let report = parse_report(&"".lines()).unwrap();
+        let trie = ReportTrie::new(&report);
+        assert_eq!(trie.count_prefix(""), 0);
+        assert_eq!(trie.count_prefix("1"), 0);
+        assert_eq!(
+            trie.rating(RulePolicy::TieToOne, true),
+            Err(RatingError::EmptyReport)
+        );
+    }
+
+    #[test]
+    fn wide_reports_only_branch_where_lines_differ() {
+        // 256 lines of 1000 bits, sharing long runs and differing in only eight columns
+        let lines = (0..256usize)
+            .map(|i| {
+                let mut line = vec!['0'; 1000];
+                for bit in 0..8 {
+                    if i >> bit & 1 == 1 {
+                        line[100 * bit + 50] = '1';
+                    }
+                }
+                line.into_iter().collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let report = parse_report(&lines.lines()).unwrap();
+        let trie = ReportTrie::new(&report);
+        assert!(trie.nodes.len() <= 2 * report.lines.len());
+
+        assert_eq!(trie.count_prefix(&"0".repeat(51)), 128);
+        assert_eq!(trie.count_prefix(&format!("{}1", "0".repeat(50))), 128);
+        assert_eq!(trie.count_prefix(&"0".repeat(1000)), 1);
+        assert_eq!(trie.count_prefix(&"0".repeat(1001)), 0);
+
+        let walk = |most_common| {
+            let rating = trie.rating(RulePolicy::TieToOne, most_common).unwrap();
+            report
+                .lines
+                .iter()
+                .position(|line| line.0 == rating)
+                .unwrap()
+        };
+        // every column that differs is split evenly, so the most common is always 1
+        assert_eq!(walk(true), 255);
+        assert_eq!(walk(false), 0);
+    }
+}
@@ -1,58 +1,83 @@
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
-use std::fs;
 use std::str::Lines;
 
-pub fn run() {
-    let part1_file = "src/day4/input.txt";
+use crate::day4::pattern::{PatternError, Progress, WinPattern};
+use crate::error::{parse_number, parse_number_list, ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
 
-    let contents = fs::read_to_string(part1_file).expect("Something went wrong reading the file");
+pub mod pattern;
 
-    let lines = contents.lines();
-    let (calls, bingo_boards) = parse_input(&lines, 5);
-    let tournament = Tournament(bingo_boards.clone());
+/// Giant Squid: find the first and last bingo boards to win.
+pub struct Day4;
 
-    let mut tournament1 = tournament.clone();
+impl Solution for Day4 {
+    const DAY: u8 = 4;
 
-    for call in calls.clone() {
-        let winners = tournament1.call(call);
-        if winners > 0 {
-            tournament1.winners().iter().for_each(|b| {
-                //println!("{}", b);
-                println!("part 1: {}", b.score().unwrap());
-            });
-            break;
+    type Input = (Vec<u32>, Vec<BingoBoard>);
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(&input.lines(), 5)
+    }
+
+    fn part1(&self, (calls, bingo_boards): &Self::Input) -> Result<Answer, SolveError> {
+        let mut tournament = Tournament::from(bingo_boards.clone());
+
+        for &call in calls {
+            let winners = tournament.call(call);
+            if winners > 0 {
+                let winner = tournament.winners().get(0).unwrap().clone();
+                return Ok(winner.score()?.points.into());
+            }
         }
+
+        Err(SolveError::NoAnswer(
+            "no board won the tournament".to_string(),
+        ))
     }
 
-    let mut tournament2 = tournament;
-    for call in calls.clone() {
-        let winners = tournament2.call(call);
+    fn part2(&self, (calls, bingo_boards): &Self::Input) -> Result<Answer, SolveError> {
+        let mut tournament = Tournament::from(bingo_boards.clone());
 
-        if winners == bingo_boards.len() as u32 {
-            let mut winning_boards = tournament2.winners();
-            winning_boards.sort_by(|a, b| a.calls.cmp(&b.calls));
+        for &call in calls {
+            let winners = tournament.call(call);
 
-            let last_winner = winning_boards.pop().unwrap();
-            //println!("{}", last_winner);
-            println!("part 2: {}", last_winner.score().unwrap());
-            break;
+            if winners == bingo_boards.len() as u32 {
+                let mut winning_boards = tournament.winners();
+                winning_boards.sort_by(|a, b| a.calls.cmp(&b.calls));
+
+                let last_winner = winning_boards.pop().unwrap();
+                return Ok(last_winner.score()?.points.into());
+            }
         }
+
+        Err(SolveError::NoAnswer(
+            "not every board won the tournament".to_string(),
+        ))
     }
 }
 
+/// A number on a board and whether it has been called.
 #[derive(Debug, Clone, PartialEq)]
-struct BingoCell(u32, bool);
+pub struct BingoCell(u32, bool);
 
-impl BingoCell {
+impl From<u32> for BingoCell {
     fn from(value: u32) -> BingoCell {
         BingoCell(value, false)
     }
+}
 
-    fn is_marked(&self) -> bool {
+impl BingoCell {
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    pub fn is_marked(&self) -> bool {
         self.1
     }
 
-    fn call(&mut self, value: u32) -> bool {
+    /// Marks the cell if it holds `value`, returning whether it was newly marked.
+    pub fn call(&mut self, value: u32) -> bool {
         if self.0 == value && !self.1 {
             self.1 = true;
             return true;
@@ -71,94 +96,147 @@ impl Display for BingoCell {
     }
 }
 
+/// A winning board's score and the part of its [`WinPattern`] that won it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub points: u64,
+    pub pattern: WinPattern,
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.points, self.pattern)
+    }
+}
+
+/// A grid of cells, stored row by row, that stops taking calls once it has won.
+///
+/// Each board indexes its cells by value and tracks its win pattern as marks are made, so a
+/// call only touches the cells holding that value and a win is noticed as it happens.
 #[derive(Debug, Clone, Default)]
-struct BingoBoard {
+pub struct BingoBoard {
     calls: u32,
     winning_number: Option<u32>,
+    winning_pattern: Option<WinPattern>,
     row_width: u32,
     col_height: u32,
     board: Vec<BingoCell>,
+    cells_by_value: HashMap<u32, Vec<usize>>,
+    progress: Progress,
 }
 
 impl BingoBoard {
-    fn from(board: Vec<u32>, row_width: u32, col_height: u32) -> BingoBoard {
+    /// A board from its numbers in row order, won by a full row or column. A board with no
+    /// rows or no columns can't be won.
+    pub fn from(board: Vec<u32>, row_width: u32, col_height: u32) -> BingoBoard {
+        let mut cells_by_value: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, &value) in board.iter().enumerate() {
+            cells_by_value.entry(value).or_default().push(i);
+        }
         let cells = board.into_iter().map(BingoCell::from).collect();
+        let (width, height) = (row_width as usize, col_height as usize);
+        let progress = Progress::new(WinPattern::default(), width, height)
+            .or_else(|_| Progress::new(WinPattern::AnyOf(vec![]), width, height))
+            .expect("an empty any-of fits any board");
 
         BingoBoard {
             row_width,
             col_height,
             board: cells,
+            cells_by_value,
+            progress,
             ..BingoBoard::default()
         }
     }
 
-    fn call(&mut self, value: u32) -> u32 {
-        let mut total = 0;
-        if self.winning_number.is_none() {
-            self.calls += 1;
-            self.board.iter_mut().for_each(|cell| {
-                let marked = cell.call(value);
-                if marked {
-                    total += 1
-                }
-            });
+    /// The same numbers, unmarked, won by `pattern` instead, if it can be played on this board.
+    pub fn with_pattern(self, pattern: WinPattern) -> Result<BingoBoard, PatternError> {
+        let progress = Progress::new(pattern, self.row_width as usize, self.col_height as usize)?;
+        let values = self.board.iter().map(BingoCell::value).collect();
+        Ok(BingoBoard {
+            progress,
+            ..BingoBoard::from(values, self.row_width, self.col_height)
+        })
+    }
 
-            if self.has_won() {
-                self.winning_number = Some(value);
-            }
-        }
-        total
+    pub fn pattern(&self) -> &WinPattern {
+        self.progress.pattern()
     }
 
-    fn has_won(&self) -> bool {
-        // check for full row
-        for row in 0..self.col_height {
-            let mut marked = 0u32;
-            let index: usize = (self.row_width * row) as usize;
+    /// How many calls this board took, up to and including its winning one.
+    pub fn calls(&self) -> u32 {
+        self.calls
+    }
 
-            for col in 0..self.row_width {
-                if self.board.get(index + (col as usize)).unwrap().is_marked() {
-                    marked += 1;
-                }
-            }
+    pub fn winning_number(&self) -> Option<u32> {
+        self.winning_number
+    }
 
-            if marked == self.row_width {
-                return true;
-            }
+    pub fn cells(&self) -> &[BingoCell] {
+        &self.board
+    }
+
+    /// Marks every cell holding `value`, returning how many were marked.
+    pub fn call(&mut self, value: u32) -> u32 {
+        if self.winning_number.is_some() {
+            return 0;
         }
+        self.calls += 1;
+        self.mark(value)
+    }
 
-        // check for full col
-        for col in 0..self.row_width {
-            let mut marked = 0u32;
-            for row in 0..self.col_height {
-                let index: usize = (col + self.row_width * row) as usize;
-                if self.board.get(index).unwrap().is_marked() {
-                    marked += 1;
-                }
+    /// Marks the cells holding `value` without counting a call.
+    fn mark(&mut self, value: u32) -> u32 {
+        let mut total = 0;
+        let cells = match self.cells_by_value.get(&value) {
+            Some(cells) => cells,
+            None => return 0,
+        };
+
+        for &i in cells {
+            if !self.board[i].call(value) {
+                continue;
             }
+            total += 1;
 
-            if marked == self.col_height {
-                return true;
+            if let Some(pattern) = self.progress.mark(i) {
+                if self.winning_number.is_none() {
+                    self.winning_number = Some(value);
+                    self.winning_pattern = Some(pattern);
+                }
             }
         }
+        total
+    }
 
-        false
+    /// Whether the board's pattern is complete.
+    pub fn has_won(&self) -> bool {
+        self.winning_number.is_some()
     }
 
-    fn score(&self) -> Result<u32, String> {
-        if self.winning_number.is_none() {
-            return Err("You must win before calculating score".to_string());
-        }
+    /// The sum of the unmarked cells times the winning number, and what won. Fails if the
+    /// board hasn't won, or the score doesn't fit in a `u64`.
+    pub fn score(&self) -> Result<Score, SolveError> {
+        let (winning_number, pattern) = match (self.winning_number, &self.winning_pattern) {
+            (Some(number), Some(pattern)) => (number, pattern.clone()),
+            _ => return Err(SolveError::NoAnswer("the board hasn't won yet".to_string())),
+        };
+        let overflow = || SolveError::NoAnswer("the board's score overflowed".to_string());
 
         // sum unmarked cells
-        let unmarked: u32 = self
+        let unmarked = self
             .board
             .iter()
             .filter(|&cell| !cell.is_marked())
-            .map(|cell| cell.0)
-            .sum();
-
-        Ok(unmarked * self.winning_number.unwrap())
+            .try_fold(0u64, |sum, cell| sum.checked_add(cell.0 as u64))
+            .ok_or_else(overflow)?;
+
+        Ok(Score {
+            points: unmarked
+                .checked_mul(winning_number as u64)
+                .ok_or_else(overflow)?,
+            pattern,
+        })
     }
 }
 
@@ -174,20 +252,73 @@ impl Display for BingoBoard {
     }
 }
 
+/// Every board playing against the same calls.
+///
+/// The tournament indexes which boards hold each value, so a call only visits those boards and
+/// a whole game is linear in the size of the boards plus the calls. A board's `calls()` catches
+/// up whenever a call marks it, so it is exact for every winner.
 #[derive(Debug, Clone)]
-struct Tournament(Vec<BingoBoard>);
+pub struct Tournament {
+    boards: Vec<BingoBoard>,
+    boards_by_value: HashMap<u32, Vec<usize>>,
+    calls: u32,
+    winners: u32,
+}
+
+impl From<Vec<BingoBoard>> for Tournament {
+    fn from(boards: Vec<BingoBoard>) -> Tournament {
+        let mut boards_by_value: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, board) in boards.iter().enumerate() {
+            for &value in board.cells_by_value.keys() {
+                boards_by_value.entry(value).or_default().push(i);
+            }
+        }
+        let winners = boards.iter().filter(|board| board.has_won()).count() as u32;
+
+        Tournament {
+            boards,
+            boards_by_value,
+            calls: 0,
+            winners,
+        }
+    }
+}
 
 impl Tournament {
-    fn call(&mut self, value: u32) -> u32 {
-        self.0.iter_mut().for_each(|board| {
-            board.call(value);
-        });
+    /// The same boards, unmarked, each won by `pattern` instead, if it can be played on all
+    /// of them.
+    pub fn with_pattern(self, pattern: WinPattern) -> Result<Tournament, PatternError> {
+        let boards = self
+            .boards
+            .into_iter()
+            .map(|board| board.with_pattern(pattern.clone()))
+            .collect::<Result<Vec<BingoBoard>, PatternError>>()?;
+        Ok(Tournament::from(boards))
+    }
+
+    /// Calls `value` on every board, returning how many boards have now won.
+    pub fn call(&mut self, value: u32) -> u32 {
+        self.calls += 1;
+        if let Some(holders) = self.boards_by_value.get(&value) {
+            for &i in holders {
+                let board = &mut self.boards[i];
+                if board.has_won() {
+                    continue;
+                }
+                board.calls = self.calls;
+                board.mark(value);
+                if board.has_won() {
+                    self.winners += 1;
+                }
+            }
+        }
 
-        self.0.iter().filter(|board| board.has_won()).count() as u32
+        self.winners
     }
 
-    fn winners(&self) -> Vec<BingoBoard> {
-        self.0
+    /// The boards that have won, in their original order.
+    pub fn winners(&self) -> Vec<BingoBoard> {
+        self.boards
             .iter()
             .filter(|board| board.has_won())
             .cloned()
@@ -195,28 +326,45 @@ impl Tournament {
     }
 }
 
-fn parse_input(input: &Lines, board_size: u32) -> (Vec<u32>, Vec<BingoBoard>) {
-    let mut input = input.clone().peekable();
-    let calls = input
+/// The called numbers on the first line, then blank line separated square boards of `board_size`.
+pub fn parse_input(
+    input: &Lines,
+    board_size: u32,
+) -> Result<(Vec<u32>, Vec<BingoBoard>), ParseError> {
+    let end_of_input = input.clone().count() + 1;
+    let mut input = input
+        .clone()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l))
+        .peekable();
+
+    let (line, calls) = input
         .next()
-        .unwrap()
-        .split(',')
-        .map(|i| i.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>();
-    let _whitespace = input.next().unwrap();
+        .ok_or_else(|| ParseError::end_of_input(1, "expected the called numbers"))?;
+    let calls = parse_number_list::<u32>(line, Span::line(calls))?;
+    let _whitespace = input.next();
 
     let mut boards: Vec<BingoBoard> = vec![];
     loop {
         let mut board: Vec<Vec<u32>> = vec![];
         for _ in 0..board_size {
-            board.push(
-                input
-                    .next()
-                    .unwrap()
-                    .split_ascii_whitespace()
-                    .map(|i| i.parse::<u32>().unwrap())
-                    .collect::<Vec<u32>>(),
-            );
+            let (line, row) = input
+                .next()
+                .ok_or_else(|| ParseError::end_of_input(end_of_input, "expected a board row"))?;
+            let cells = Span::line(row)
+                .words()
+                .into_iter()
+                .map(|i| parse_number::<u32>(line, i))
+                .collect::<Result<Vec<u32>, ParseError>>()?;
+
+            if cells.len() != board_size as usize {
+                return Err(ParseError::at(
+                    line,
+                    Span::line(row),
+                    &format!("expected {} numbers in a board row", board_size),
+                ));
+            }
+            board.push(cells);
         }
 
         let bingo_board = BingoBoard::from(board.concat(), board_size, board_size);
@@ -224,19 +372,22 @@ fn parse_input(input: &Lines, board_size: u32) -> (Vec<u32>, Vec<BingoBoard>) {
 
         let _whitespace = input.next();
 
-        if input.peek() == None {
+        if input.peek().is_none() {
             break;
         }
     }
 
-    (calls, boards)
+    Ok((calls, boards))
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use crate::day4::{parse_input, BingoBoard, BingoCell, Tournament};
+    use crate::day4::pattern::{PatternError, WinPattern};
+    use crate::day4::{parse_input, BingoBoard, BingoCell, Day4, Score, Tournament};
+    use crate::error::ParseError;
+    use crate::solution::{Solution, SolveError};
 
     #[test]
     fn bingo_cell_starts_unmarked() {
@@ -263,12 +414,7 @@ mod tests {
 
     #[test]
     fn bingo_board_marks_cells() {
-        let mut board = BingoBoard {
-            row_width: 1,
-            col_height: 1,
-            board: vec![BingoCell::from(3)],
-            ..BingoBoard::default()
-        };
+        let mut board = BingoBoard::from(vec![3], 1, 1);
 
         let marked = board.call(3);
         assert_eq!(marked, 1);
@@ -277,18 +423,7 @@ mod tests {
 
     #[test]
     fn bingo_board_marks_all_cells_that_match() {
-        let mut board = BingoBoard {
-            row_width: 5,
-            col_height: 1,
-            board: vec![
-                BingoCell::from(1),
-                BingoCell::from(2),
-                BingoCell::from(3),
-                BingoCell::from(5),
-                BingoCell::from(5),
-            ],
-            ..BingoBoard::default()
-        };
+        let mut board = BingoBoard::from(vec![1, 2, 3, 5, 5], 5, 1);
 
         let marked = board.call(5);
         assert_eq!(marked, 2);
@@ -298,20 +433,13 @@ mod tests {
 
     #[test]
     fn bingo_board_checks_for_wins() {
-        let mut board = BingoBoard {
-            row_width: 2,
-            col_height: 2,
-
+        let mut board = BingoBoard::from(
             // 1 2
             // 3 4
-            board: vec![
-                BingoCell::from(1),
-                BingoCell::from(2),
-                BingoCell::from(3),
-                BingoCell::from(4),
-            ],
-            ..BingoBoard::default()
-        };
+            vec![1, 2, 3, 4],
+            2,
+            2,
+        );
 
         assert!(!board.has_won());
         board.call(1);
@@ -321,20 +449,13 @@ mod tests {
 
     #[test]
     fn bingo_board_tracks_winning_call() {
-        let mut board = BingoBoard {
-            row_width: 2,
-            col_height: 2,
-
+        let mut board = BingoBoard::from(
             // 1 2
             // 3 4
-            board: vec![
-                BingoCell::from(1),
-                BingoCell::from(2),
-                BingoCell::from(3),
-                BingoCell::from(4),
-            ],
-            ..BingoBoard::default()
-        };
+            vec![1, 2, 3, 4],
+            2,
+            2,
+        );
 
         board.call(1);
         assert_eq!(board.winning_number, None);
@@ -346,20 +467,13 @@ mod tests {
 
     #[test]
     fn bingo_board_checks_for_a_winning_row() {
-        let mut board = BingoBoard {
-            row_width: 2,
-            col_height: 2,
-
+        let mut board = BingoBoard::from(
             // 1 2
             // 3 4
-            board: vec![
-                BingoCell::from(1),
-                BingoCell::from(2),
-                BingoCell::from(3),
-                BingoCell::from(4),
-            ],
-            ..BingoBoard::default()
-        };
+            vec![1, 2, 3, 4],
+            2,
+            2,
+        );
 
         board.call(1);
         board.call(2);
@@ -368,20 +482,13 @@ mod tests {
 
     #[test]
     fn bingo_board_checks_for_a_winning_col() {
-        let mut board = BingoBoard {
-            row_width: 2,
-            col_height: 2,
-
+        let mut board = BingoBoard::from(
             // 1 2
             // 3 4
-            board: vec![
-                BingoCell::from(1),
-                BingoCell::from(2),
-                BingoCell::from(3),
-                BingoCell::from(4),
-            ],
-            ..BingoBoard::default()
-        };
+            vec![1, 2, 3, 4],
+            2,
+            2,
+        );
 
         board.call(2);
         board.call(4);
@@ -390,44 +497,19 @@ mod tests {
 
     #[test]
     fn bingo_board_checks_larger_boards() {
-        let board = BingoBoard {
-            row_width: 5,
-            col_height: 5,
-
+        let board = BingoBoard::from(
             //  1  2  3  4  5
             //  6  7  8  9 10
             // 11 12 13 14 15
             // 16 17 18 19 20
             // 21 22 23 24 25
-            board: vec![
-                BingoCell::from(1),
-                BingoCell::from(2),
-                BingoCell::from(3),
-                BingoCell::from(4),
-                BingoCell::from(5),
-                BingoCell::from(6),
-                BingoCell::from(7),
-                BingoCell::from(8),
-                BingoCell::from(9),
-                BingoCell::from(10),
-                BingoCell::from(11),
-                BingoCell::from(12),
-                BingoCell::from(13),
-                BingoCell::from(14),
-                BingoCell::from(15),
-                BingoCell::from(16),
-                BingoCell::from(17),
-                BingoCell::from(18),
-                BingoCell::from(19),
-                BingoCell::from(20),
-                BingoCell::from(21),
-                BingoCell::from(22),
-                BingoCell::from(23),
-                BingoCell::from(24),
-                BingoCell::from(25),
+            vec![
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+                24, 25,
             ],
-            ..BingoBoard::default()
-        };
+            5,
+            5,
+        );
         assert!(!board.has_won());
 
         let mut board1 = board.clone();
@@ -465,44 +547,19 @@ mod tests {
 
     #[test]
     fn bingo_board_gets_score() {
-        let mut board = BingoBoard {
-            row_width: 5,
-            col_height: 5,
-
+        let mut board = BingoBoard::from(
             // 14 21 17 24  4
             // 10 16 15  9 19
             // 18  8 23 26 20
             // 22 11 13  6  5
             //  2  0 12  3  7
-            board: vec![
-                BingoCell::from(14),
-                BingoCell::from(21),
-                BingoCell::from(17),
-                BingoCell::from(24),
-                BingoCell::from(4),
-                BingoCell::from(10),
-                BingoCell::from(16),
-                BingoCell::from(15),
-                BingoCell::from(9),
-                BingoCell::from(19),
-                BingoCell::from(18),
-                BingoCell::from(8),
-                BingoCell::from(23),
-                BingoCell::from(26),
-                BingoCell::from(20),
-                BingoCell::from(22),
-                BingoCell::from(11),
-                BingoCell::from(13),
-                BingoCell::from(6),
-                BingoCell::from(5),
-                BingoCell::from(2),
-                BingoCell::from(0),
-                BingoCell::from(12),
-                BingoCell::from(3),
-                BingoCell::from(7),
+            vec![
+                14, 21, 17, 24, 4, 10, 16, 15, 9, 19, 18, 8, 23, 26, 20, 22, 11, 13, 6, 5, 2, 0,
+                12, 3, 7,
             ],
-            ..BingoBoard::default()
-        };
+            5,
+            5,
+        );
 
         board.call(7);
         board.call(4);
@@ -517,45 +574,133 @@ mod tests {
         board.call(21);
         board.call(24);
         assert_eq!(board.winning_number, Some(24));
-        assert_eq!(board.score(), Ok(4512));
+        assert_eq!(
+            board.score(),
+            Ok(Score {
+                points: 4512,
+                pattern: WinPattern::Row
+            })
+        );
+        assert_eq!(board.score().unwrap().to_string(), "4512 (row)");
     }
 
     #[test]
-    fn tournament_calls_each_board() {
-        let mut tournament = Tournament(vec![
-            BingoBoard {
-                row_width: 2,
-                col_height: 2,
+    fn big_scores_dont_overflow() {
+        // a column of 100000s, with the other cells from 70000 up
+        let mut others = 70000..;
+        let numbers = (0..25)
+            .map(|i| match i % 5 {
+                2 => 100000,
+                _ => others.next().unwrap(),
+            })
+            .collect();
+        let mut board = BingoBoard::from(numbers, 5, 5);
+        board.call(100000);
+        assert_eq!(
+            board.score().map(|s| s.points),
+            Ok((20 * 70000 + 190) * 100000)
+        );
+
+        let mut board = BingoBoard::from(vec![u32::MAX, 1, u32::MAX, u32::MAX - 1], 2, 2);
+        board.call(1);
+        board.call(u32::MAX - 1);
+        assert_eq!(
+            board.score(),
+            Err(SolveError::NoAnswer(
+                "the board's score overflowed".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn bingo_boards_take_win_patterns() {
+        // 1 2 3
+        // 4 5 6
+        // 7 8 9
+        let board = BingoBoard::from((1..=9).collect(), 3, 3);
+
+        let mut diagonal = board.clone().with_pattern(WinPattern::Diagonal).unwrap();
+        for value in [1, 2, 3, 5] {
+            diagonal.call(value);
+        }
+        assert!(!diagonal.has_won());
+        diagonal.call(7);
+        assert_eq!(
+            diagonal.score(),
+            Ok(Score {
+                points: (4 + 6 + 8 + 9) * 7,
+                pattern: WinPattern::Diagonal
+            })
+        );
+
+        let corners_or_middle = WinPattern::AnyOf(vec![
+            WinPattern::Corners,
+            WinPattern::Mask(vec![1, 3, 4, 5, 7]),
+        ]);
+        let mut plus = board.clone().with_pattern(corners_or_middle).unwrap();
+        for value in [1, 2, 4, 5, 6, 9] {
+            plus.call(value);
+        }
+        plus.call(8);
+        assert_eq!(plus.winning_number(), Some(8));
+        assert_eq!(
+            plus.score().map(|s| s.pattern),
+            Ok(WinPattern::Mask(vec![1, 3, 4, 5, 7]))
+        );
+
+        let mut blackout = board.clone().with_pattern(WinPattern::Blackout).unwrap();
+        for value in 1..9 {
+            blackout.call(value);
+        }
+        assert!(!blackout.has_won());
+        blackout.call(9);
+        assert_eq!(blackout.score().map(|s| s.points), Ok(0));
+
+        assert_eq!(
+            board
+                .clone()
+                .with_pattern(WinPattern::Mask(vec![4, 9]))
+                .unwrap_err(),
+            PatternError::CellOutsideBoard {
+                cell: 9,
+                width: 3,
+                height: 3
+            }
+        );
+        assert_eq!(
+            board.with_pattern(WinPattern::Mask(vec![])).unwrap_err(),
+            PatternError::AlreadyComplete(WinPattern::Mask(vec![]))
+        );
 
+        // rows of nothing would be complete already, so a board without columns never wins
+        let mut empty = BingoBoard::from(vec![], 0, 3);
+        assert_eq!(empty.pattern(), &WinPattern::AnyOf(vec![]));
+        empty.call(1);
+        assert!(!empty.has_won());
+    }
+
+    #[test]
+    fn tournament_calls_each_board() {
+        let mut tournament = Tournament::from(vec![
+            BingoBoard::from(
                 // 1 2
                 // 3 4
-                board: vec![
-                    BingoCell::from(1),
-                    BingoCell::from(2),
-                    BingoCell::from(3),
-                    BingoCell::from(4),
-                ],
-                ..BingoBoard::default()
-            },
-            BingoBoard {
-                row_width: 2,
-                col_height: 2,
-
+                vec![1, 2, 3, 4],
+                2,
+                2,
+            ),
+            BingoBoard::from(
                 // 2 3
                 // 4 1
-                board: vec![
-                    BingoCell::from(2),
-                    BingoCell::from(3),
-                    BingoCell::from(4),
-                    BingoCell::from(1),
-                ],
-                ..BingoBoard::default()
-            },
+                vec![2, 3, 4, 1],
+                2,
+                2,
+            ),
         ]);
 
         tournament.call(1);
         assert!(tournament
-            .0
+            .boards
             .get(0)
             .unwrap()
             .board
@@ -563,7 +708,7 @@ mod tests {
             .unwrap()
             .is_marked());
         assert!(tournament
-            .0
+            .boards
             .get(1)
             .unwrap()
             .board
@@ -574,47 +719,110 @@ mod tests {
 
     #[test]
     fn tournament_gets_winners_after_each_call() {
-        let mut tournament = Tournament(vec![
-            BingoBoard {
-                row_width: 2,
-                col_height: 2,
-
+        let mut tournament = Tournament::from(vec![
+            BingoBoard::from(
                 // 1 2
                 // 3 4
-                board: vec![
-                    BingoCell::from(1),
-                    BingoCell::from(2),
-                    BingoCell::from(3),
-                    BingoCell::from(4),
-                ],
-                ..BingoBoard::default()
-            },
-            BingoBoard {
-                row_width: 2,
-                col_height: 2,
-
+                vec![1, 2, 3, 4],
+                2,
+                2,
+            ),
+            BingoBoard::from(
                 // 2 3
                 // 4 1
-                board: vec![
-                    BingoCell::from(2),
-                    BingoCell::from(3),
-                    BingoCell::from(4),
-                    BingoCell::from(1),
-                ],
-                ..BingoBoard::default()
-            },
+                vec![2, 3, 4, 1],
+                2,
+                2,
+            ),
         ]);
 
         assert_eq!(tournament.call(1), 0);
         assert_eq!(tournament.call(2), 1);
         assert_eq!(tournament.winners().len(), 1);
         let winner = tournament.winners().get(0).unwrap().clone();
-        assert_eq!(winner.score(), Ok(14));
+        assert_eq!(winner.score().map(|s| s.points), Ok(14));
 
         assert_eq!(tournament.call(3), 2);
         assert_eq!(tournament.winners().len(), 2);
         let winner = tournament.winners().get(1).unwrap().clone();
-        assert_eq!(winner.score(), Ok(12));
+        assert_eq!(winner.score().map(|s| s.points), Ok(12));
+    }
+
+    #[test]
+    fn tournament_counts_wins_as_they_happen() {
+        // boards only see the calls for their own numbers, but still know when they won
+        let mut tournament = Tournament::from(vec![
+            BingoBoard::from(vec![1, 2, 3, 4], 2, 2),
+            BingoBoard::from(vec![5, 6, 7, 8], 2, 2),
+            BingoBoard::from(vec![1, 5, 9, 9], 2, 2),
+        ]);
+
+        assert_eq!(tournament.call(9), 1);
+        assert_eq!(tournament.boards[2].calls(), 1);
+        assert_eq!(tournament.boards[2].winning_number(), Some(9));
+        assert_eq!(tournament.call(1), 1);
+        assert_eq!(tournament.call(6), 1);
+        assert_eq!(tournament.call(3), 2);
+        assert_eq!(tournament.boards[0].calls(), 4);
+        assert_eq!(tournament.call(8), 3);
+        assert_eq!(tournament.boards[1].calls(), 5);
+        assert_eq!(tournament.boards[1].score().map(|s| s.points), Ok(12 * 8));
+    }
+
+    #[test]
+    fn tournaments_take_win_patterns() {
+        let mut tournament = Tournament::from(vec![
+            BingoBoard::from(vec![1, 2, 3, 4], 2, 2),
+            BingoBoard::from(vec![2, 1, 5, 6], 2, 2),
+        ]);
+        tournament.call(1);
+        assert_eq!(tournament.call(2), 2);
+
+        // a new pattern starts the boards again
+        let mut tournament = tournament
+            .with_pattern(WinPattern::AllOf(vec![WinPattern::Row, WinPattern::Column]))
+            .unwrap();
+        assert_eq!(tournament.call(1), 0);
+        assert_eq!(tournament.call(2), 0);
+        assert_eq!(tournament.call(3), 1);
+        assert_eq!(
+            tournament.winners()[0].score(),
+            Ok(Score {
+                points: 4 * 3,
+                pattern: WinPattern::AllOf(vec![WinPattern::Row, WinPattern::Column])
+            })
+        );
+        assert_eq!(tournament.call(5), 2);
+
+        assert_eq!(
+            tournament
+                .with_pattern(WinPattern::Mask(vec![4]))
+                .unwrap_err(),
+            PatternError::CellOutsideBoard {
+                cell: 4,
+                width: 2,
+                height: 2
+            }
+        );
+    }
+
+    #[test]
+    fn tournaments_scale_to_many_boards() {
+        // 2000 boards, each with its own numbers, called in order
+        let boards = (0..2000)
+            .map(|b| BingoBoard::from((0..25).map(|v| b * 25 + v).collect(), 5, 5))
+            .collect::<Vec<BingoBoard>>();
+        let mut tournament = Tournament::from(boards);
+
+        let mut winners = 0;
+        for value in 0..2000 * 25 {
+            winners = tournament.call(value);
+        }
+        assert_eq!(winners, 2000);
+        assert_eq!(
+            tournament.boards[1999].winning_number(),
+            Some(1999 * 25 + 4)
+        );
     }
 
     #[test]
@@ -630,7 +838,7 @@ mod tests {
 "#;
         println!("{}", doc);
 
-        let (calls, boards) = parse_input(&doc.lines(), 2);
+        let (calls, boards) = parse_input(&doc.lines(), 2).unwrap();
         assert_eq!(calls, vec![1, 2, 3, 4]);
         assert_eq!(boards.len(), 2);
         assert_eq!(
@@ -667,6 +875,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_reports_bad_bingo_input() {
+        let err = parse_input(&"1,2,x".lines(), 2).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 5, "x", "expected a number"));
+
+        let err = parse_input(&"1,2\n\n1 2\n3".lines(), 2).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(4, 1, "3", "expected 2 numbers in a board row")
+        );
+
+        let err = parse_input(&"1,2\n\n1 2".lines(), 2).unwrap_err();
+        assert_eq!(err, ParseError::new(4, 1, "", "expected a board row"));
+    }
+
     #[test]
     fn it_parses_test_input() {
         let filename = "src/day4/test.txt";
@@ -674,7 +897,7 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let (calls, boards) = parse_input(&lines, 5);
+        let (calls, boards) = parse_input(&lines, 5).unwrap();
         assert_eq!(
             calls,
             vec![
@@ -693,8 +916,8 @@ mod tests {
             fs::read_to_string(part1_file).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let (calls, bingo_boards) = parse_input(&lines, 5);
-        let mut tournament = Tournament(bingo_boards);
+        let (calls, bingo_boards) = parse_input(&lines, 5).unwrap();
+        let mut tournament = Tournament::from(bingo_boards);
 
         let _winning_score = 0u32;
         for call in calls {
@@ -702,7 +925,7 @@ mod tests {
             if winners > 0 {
                 assert_eq!(winners, 1);
                 let winner = tournament.winners().get(0).unwrap().clone();
-                assert_eq!(winner.score(), Ok(4512));
+                assert_eq!(winner.score().map(|s| s.points), Ok(4512));
                 break;
             }
         }
@@ -716,8 +939,8 @@ mod tests {
             fs::read_to_string(part2_file).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let (calls, bingo_boards) = parse_input(&lines, 5);
-        let mut tournament = Tournament(bingo_boards.clone());
+        let (calls, bingo_boards) = parse_input(&lines, 5).unwrap();
+        let mut tournament = Tournament::from(bingo_boards.clone());
 
         for call in calls {
             let winners = tournament.call(call);
@@ -737,9 +960,27 @@ mod tests {
                 });
 
                 let last_winner = winning_boards.pop().unwrap();
-                assert_eq!(last_winner.score(), Ok(1924));
+                assert_eq!(last_winner.score().map(|s| s.points), Ok(1924));
                 break;
             }
         }
     }
+
+    #[test]
+    fn games_without_winners_have_no_answer() {
+        let board = "1 2 3 4 5\n6 7 8 9 10\n11 12 13 14 15\n16 17 18 19 20\n21 22 23 24 25\n";
+        let game = Day4.parse(&format!("1,2\n\n{}", board)).unwrap();
+        assert_eq!(
+            Day4.part1(&game),
+            Err(SolveError::NoAnswer(
+                "no board won the tournament".to_string()
+            ))
+        );
+        assert_eq!(
+            Day4.part2(&game),
+            Err(SolveError::NoAnswer(
+                "not every board won the tournament".to_string()
+            ))
+        );
+    }
 }
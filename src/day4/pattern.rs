@@ -0,0 +1,400 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Which marked cells win a board. Cells are numbered in row order, like [`super::BingoBoard::from`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum WinPattern {
+    /// Any full row.
+    Row,
+    /// Any full column.
+    Column,
+    /// Either full diagonal. Only square boards have diagonals.
+    Diagonal,
+    /// All four corners.
+    Corners,
+    /// Every cell.
+    Blackout,
+    /// Every one of these cells.
+    Mask(Vec<usize>),
+    /// At least one of these patterns.
+    AnyOf(Vec<WinPattern>),
+    /// All of these patterns, in any order.
+    AllOf(Vec<WinPattern>),
+}
+
+/// The puzzle's rules: any full row or column.
+impl Default for WinPattern {
+    fn default() -> WinPattern {
+        WinPattern::AnyOf(vec![WinPattern::Row, WinPattern::Column])
+    }
+}
+
+impl Display for WinPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let list = |f: &mut Formatter<'_>, name: &str, patterns: &[WinPattern]| {
+            let patterns = patterns
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            write!(f, "{} ({})", name, patterns)
+        };
+
+        match self {
+            WinPattern::Row => write!(f, "row"),
+            WinPattern::Column => write!(f, "column"),
+            WinPattern::Diagonal => write!(f, "diagonal"),
+            WinPattern::Corners => write!(f, "four corners"),
+            WinPattern::Blackout => write!(f, "blackout"),
+            WinPattern::Mask(cells) => write!(f, "mask {:?}", cells),
+            WinPattern::AnyOf(patterns) => list(f, "any of", patterns),
+            WinPattern::AllOf(patterns) => list(f, "all of", patterns),
+        }
+    }
+}
+
+/// Why a [`WinPattern`] can't be played on a board.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternError {
+    /// A mask names a cell past the end of the board.
+    CellOutsideBoard {
+        cell: usize,
+        width: usize,
+        height: usize,
+    },
+    /// A pattern with nothing to mark, like an empty mask or all-of, so no call would be the one
+    /// that completes it.
+    AlreadyComplete(WinPattern),
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternError::CellOutsideBoard {
+                cell,
+                width,
+                height,
+            } => write!(f, "cell {} is outside a {}x{} board", cell, width, height),
+            PatternError::AlreadyComplete(pattern) => {
+                write!(f, "{} is complete before any cell is marked", pattern)
+            }
+        }
+    }
+}
+
+impl Error for PatternError {}
+
+impl WinPattern {
+    /// The groups of cells that each complete a basic pattern, or `None` for any-of and all-of.
+    fn groups(&self, width: usize, height: usize) -> Option<Vec<Vec<usize>>> {
+        let cells = width * height;
+        let groups = match self {
+            WinPattern::Row => (0..height)
+                .map(|row| (row * width..(row + 1) * width).collect())
+                .collect(),
+            WinPattern::Column => (0..width)
+                .map(|col| (0..height).map(|row| row * width + col).collect())
+                .collect(),
+            WinPattern::Diagonal if width == height && width > 0 => vec![
+                (0..width).map(|i| i * width + i).collect(),
+                (0..width).map(|i| i * width + width - 1 - i).collect(),
+            ],
+            WinPattern::Diagonal => vec![],
+            WinPattern::Corners if cells > 0 => {
+                let mut corners = vec![0, width - 1, cells - width, cells - 1];
+                corners.sort_unstable();
+                corners.dedup();
+                vec![corners]
+            }
+            WinPattern::Corners => vec![],
+            WinPattern::Blackout => vec![(0..cells).collect()],
+            WinPattern::Mask(mask) => {
+                let mut mask = mask.clone();
+                mask.sort_unstable();
+                mask.dedup();
+                vec![mask]
+            }
+            WinPattern::AnyOf(_) | WinPattern::AllOf(_) => return None,
+        };
+        Some(groups)
+    }
+}
+
+/// Tracks a [`WinPattern`] on one board as cells are marked.
+///
+/// Every basic pattern becomes groups of cells with a count of marks in each, so marking a cell
+/// only bumps the counts of the groups it belongs to. The pattern as a whole is only checked
+/// again when one of its basic patterns is newly complete.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Progress {
+    pattern: WinPattern,
+    /// Whether each basic pattern is complete, in the order they appear in `pattern`.
+    complete: Vec<bool>,
+    group_sizes: Vec<usize>,
+    group_marks: Vec<usize>,
+    /// The basic pattern each group belongs to.
+    group_pattern: Vec<usize>,
+    groups_by_cell: Vec<Vec<usize>>,
+}
+
+impl Progress {
+    /// Fails if a mask names a cell outside the board, or part of the pattern is complete before
+    /// any cell is marked, so it would never be reported as a win.
+    pub fn new(pattern: WinPattern, width: usize, height: usize) -> Result<Progress, PatternError> {
+        let mut progress = Progress {
+            pattern: pattern.clone(),
+            groups_by_cell: vec![vec![]; width * height],
+            ..Progress::default()
+        };
+        progress.add(&pattern, width, height)?;
+        Ok(progress)
+    }
+
+    fn add(
+        &mut self,
+        pattern: &WinPattern,
+        width: usize,
+        height: usize,
+    ) -> Result<(), PatternError> {
+        match pattern.groups(width, height) {
+            Some(groups) => {
+                let outside = groups
+                    .iter()
+                    .flatten()
+                    .find(|&&cell| cell >= width * height);
+                if let Some(&cell) = outside {
+                    return Err(PatternError::CellOutsideBoard {
+                        cell,
+                        width,
+                        height,
+                    });
+                }
+                if groups.iter().any(|group| group.is_empty()) {
+                    return Err(PatternError::AlreadyComplete(pattern.clone()));
+                }
+
+                let index = self.complete.len();
+                self.complete.push(false);
+                for group in groups {
+                    let id = self.group_sizes.len();
+                    self.group_sizes.push(group.len());
+                    self.group_marks.push(0);
+                    self.group_pattern.push(index);
+                    for cell in group {
+                        self.groups_by_cell[cell].push(id);
+                    }
+                }
+            }
+            None => {
+                if *pattern == WinPattern::AllOf(vec![]) {
+                    return Err(PatternError::AlreadyComplete(pattern.clone()));
+                }
+                if let WinPattern::AnyOf(patterns) | WinPattern::AllOf(patterns) = pattern {
+                    for pattern in patterns {
+                        self.add(pattern, width, height)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn pattern(&self) -> &WinPattern {
+        &self.pattern
+    }
+
+    /// Records a newly marked cell, returning the part of the pattern that won if this mark
+    /// completed it.
+    pub fn mark(&mut self, cell: usize) -> Option<WinPattern> {
+        let mut changed = false;
+        for &group in self.groups_by_cell[cell].iter() {
+            self.group_marks[group] += 1;
+            if self.group_marks[group] == self.group_sizes[group] {
+                let index = self.group_pattern[group];
+                changed |= !self.complete[index];
+                self.complete[index] = true;
+            }
+        }
+
+        if changed {
+            self.winner()
+        } else {
+            None
+        }
+    }
+
+    /// The part of the pattern that is complete, if the pattern is: the first complete
+    /// alternative of an any-of, all of an all-of.
+    pub fn winner(&self) -> Option<WinPattern> {
+        self.winner_from(&self.pattern, &mut 0)
+    }
+
+    /// Walks every basic pattern under `pattern`, so `next` stays in step with `complete`.
+    fn winner_from(&self, pattern: &WinPattern, next: &mut usize) -> Option<WinPattern> {
+        match pattern {
+            WinPattern::AnyOf(patterns) => patterns
+                .iter()
+                .map(|p| self.winner_from(p, next))
+                .collect::<Vec<Option<WinPattern>>>()
+                .into_iter()
+                .flatten()
+                .next(),
+            WinPattern::AllOf(patterns) => patterns
+                .iter()
+                .map(|p| self.winner_from(p, next))
+                .collect::<Option<Vec<WinPattern>>>()
+                .map(WinPattern::AllOf),
+            basic => {
+                let complete = self.complete[*next];
+                *next += 1;
+                if complete {
+                    Some(basic.clone())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day4::pattern::{PatternError, Progress, WinPattern};
+
+    /// Marks `cells` on a `size` square board, returning what won and on which mark.
+    fn play(pattern: WinPattern, size: usize, cells: &[usize]) -> Option<(usize, WinPattern)> {
+        let mut progress = Progress::new(pattern, size, size).unwrap();
+        cells
+            .iter()
+            .enumerate()
+            .find_map(|(i, &cell)| progress.mark(cell).map(|won| (i + 1, won)))
+    }
+
+    #[test]
+    fn basic_patterns_win() {
+        // 0 1 2
+        // 3 4 5
+        // 6 7 8
+        assert_eq!(
+            play(WinPattern::Row, 3, &[3, 0, 4, 5]),
+            Some((4, WinPattern::Row))
+        );
+        assert_eq!(
+            play(WinPattern::Column, 3, &[1, 4, 3, 7]),
+            Some((4, WinPattern::Column))
+        );
+        assert_eq!(
+            play(WinPattern::Diagonal, 3, &[2, 4, 0, 6]),
+            Some((4, WinPattern::Diagonal))
+        );
+        assert_eq!(
+            play(WinPattern::Corners, 3, &[0, 2, 4, 6, 8]),
+            Some((5, WinPattern::Corners))
+        );
+        assert_eq!(
+            play(WinPattern::Blackout, 3, &[0, 1, 2, 3, 4, 5, 6, 7]),
+            None
+        );
+        assert_eq!(
+            play(WinPattern::Mask(vec![1, 3, 5, 7]), 3, &[1, 3, 5, 0, 7]),
+            Some((5, WinPattern::Mask(vec![1, 3, 5, 7])))
+        );
+        assert_eq!(play(WinPattern::Row, 3, &[0, 4, 8]), None);
+    }
+
+    #[test]
+    fn patterns_combine() {
+        let row_or_diagonal = WinPattern::AnyOf(vec![WinPattern::Row, WinPattern::Diagonal]);
+        assert_eq!(
+            play(row_or_diagonal.clone(), 3, &[0, 4, 8]),
+            Some((3, WinPattern::Diagonal))
+        );
+        assert_eq!(
+            play(row_or_diagonal, 3, &[6, 7, 8]),
+            Some((3, WinPattern::Row))
+        );
+
+        let corners_and_column = WinPattern::AllOf(vec![WinPattern::Corners, WinPattern::Column]);
+        // the first column finishes before the last corner
+        assert_eq!(
+            play(corners_and_column, 3, &[0, 3, 6, 2, 8]),
+            Some((
+                5,
+                WinPattern::AllOf(vec![WinPattern::Corners, WinPattern::Column])
+            ))
+        );
+
+        let nested = WinPattern::AnyOf(vec![
+            WinPattern::Blackout,
+            WinPattern::AllOf(vec![WinPattern::Row, WinPattern::Column]),
+        ]);
+        assert_eq!(play(nested.clone(), 2, &[0, 1]), None);
+        assert_eq!(
+            play(nested, 2, &[0, 1, 2]),
+            Some((
+                3,
+                WinPattern::AllOf(vec![WinPattern::Row, WinPattern::Column])
+            ))
+        );
+    }
+
+    #[test]
+    fn diagonals_need_a_square_board() {
+        let mut progress = Progress::new(WinPattern::Diagonal, 3, 2).unwrap();
+        assert!((0..6).all(|cell| progress.mark(cell).is_none()));
+    }
+
+    #[test]
+    fn masks_must_fit_the_board() {
+        let err = Progress::new(WinPattern::Mask(vec![0, 9]), 3, 3).unwrap_err();
+        assert_eq!(
+            err,
+            PatternError::CellOutsideBoard {
+                cell: 9,
+                width: 3,
+                height: 3
+            }
+        );
+        assert_eq!(err.to_string(), "cell 9 is outside a 3x3 board");
+
+        let nested = WinPattern::AnyOf(vec![WinPattern::Row, WinPattern::Mask(vec![25])]);
+        assert!(Progress::new(nested.clone(), 5, 5).is_err());
+        assert!(Progress::new(nested, 6, 5).is_ok());
+    }
+
+    #[test]
+    fn patterns_must_need_a_mark() {
+        let err = Progress::new(WinPattern::Mask(vec![]), 3, 3).unwrap_err();
+        assert_eq!(err, PatternError::AlreadyComplete(WinPattern::Mask(vec![])));
+        assert_eq!(
+            err.to_string(),
+            "mask [] is complete before any cell is marked"
+        );
+
+        let nested = WinPattern::AllOf(vec![WinPattern::Corners, WinPattern::Mask(vec![])]);
+        assert_eq!(
+            Progress::new(nested, 3, 3),
+            Err(PatternError::AlreadyComplete(WinPattern::Mask(vec![])))
+        );
+        // a board with no columns has empty rows
+        assert_eq!(
+            Progress::new(WinPattern::Row, 0, 3),
+            Err(PatternError::AlreadyComplete(WinPattern::Row))
+        );
+        assert_eq!(
+            Progress::new(WinPattern::AllOf(vec![]), 3, 3),
+            Err(PatternError::AlreadyComplete(WinPattern::AllOf(vec![])))
+        );
+        // an empty any-of never wins, which isn't the same as having won
+        assert!(Progress::new(WinPattern::AnyOf(vec![]), 3, 3).is_ok());
+    }
+
+    #[test]
+    fn patterns_describe_themselves() {
+        assert_eq!(WinPattern::default().to_string(), "any of (row, column)");
+        assert_eq!(
+            WinPattern::AllOf(vec![WinPattern::Corners, WinPattern::Mask(vec![4])]).to_string(),
+            "all of (four corners, mask [4])"
+        );
+    }
+}
@@ -1,38 +1,58 @@
-use std::fmt::{Debug, Display, Error, Formatter};
-use std::fs;
+use std::fmt::{Debug, Display, Formatter};
 use std::str::{FromStr, Lines};
 
-pub fn run() {
-    let file = "src/day5/input.txt";
+use crate::error::{parse_number, ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
 
-    let contents = fs::read_to_string(file).expect("Something went wrong reading the file");
+/// Hydrothermal Venture: count the points where vent lines overlap.
+pub struct Day5;
 
-    let lines = contents.lines();
-    let sea_floor1 = parse_input(lines.clone(), 1000, false);
+/// How far the mapped sea floor reaches in `x` and `y`. Vents have to fit inside it.
+pub const FLOOR_WIDTH: usize = 1000;
 
-    let dangerous_areas = sea_floor1
-        .0
-        .iter()
-        .flatten()
-        .filter(|&c| *c >= 2u32)
-        .count();
-    println!("part 1: {}", dangerous_areas);
+impl Solution for Day5 {
+    const DAY: u8 = 5;
 
-    let sea_floor2 = parse_input(lines.clone(), 1000, true);
+    type Input = Vec<Range>;
 
-    let dangerous_areas = sea_floor2
-        .0
-        .iter()
-        .flatten()
-        .filter(|&c| *c >= 2u32)
-        .count();
-    println!("part 2: {}", dangerous_areas);
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        parse_vents(input.lines())
+    }
+
+    fn part1(&self, vents: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(SeaFloor::map(vents, FLOOR_WIDTH, false)
+            .dangerous_areas()
+            .into())
+    }
+
+    fn part2(&self, vents: &Self::Input) -> Result<Answer, SolveError> {
+        Ok(SeaFloor::map(vents, FLOOR_WIDTH, true)
+            .dangerous_areas()
+            .into())
+    }
 }
 
+/// How many vents cover each point of a square area of the sea floor, indexed `[y][x]`.
 #[derive(Debug, PartialEq)]
-struct SeaFloor(Vec<Vec<u32>>);
+pub struct SeaFloor(Vec<Vec<u32>>);
 
 impl SeaFloor {
+    /// Maps `vents` onto a `width` by `width` area, skipping diagonal vents unless `use_diagonals`.
+    pub fn map(vents: &[Range], width: usize, use_diagonals: bool) -> SeaFloor {
+        let mut sea_floor = SeaFloor(vec![vec![0; width]; width]);
+
+        for &vent in vents {
+            sea_floor.map_vent(vent, use_diagonals);
+        }
+
+        sea_floor
+    }
+
+    /// How many points at least two vents cover.
+    pub fn dangerous_areas(&self) -> usize {
+        self.0.iter().flatten().filter(|&c| *c >= 2u32).count()
+    }
+
     fn map_vent(&mut self, vent: Range, use_diagonals: bool) {
         if vent.is_cardinal() || use_diagonals {
             for location in vent.locations() {
@@ -41,11 +61,11 @@ impl SeaFloor {
         }
     }
 
-    fn get_width(&self) -> usize {
+    pub fn get_width(&self) -> usize {
         self.0[0].len()
     }
 
-    fn get_height(&self) -> usize {
+    pub fn get_height(&self) -> usize {
         self.0.len()
     }
 }
@@ -67,23 +87,47 @@ impl Display for SeaFloor {
     }
 }
 
+/// An `x,y` point on the sea floor.
 #[derive(Debug, Copy, Clone, PartialEq)]
-struct Coordinate(usize, usize);
+pub struct Coordinate(pub usize, pub usize);
 
 impl FromStr for Coordinate {
-    type Err = Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let n: Vec<usize> = s.split(',').map(|a| a.parse::<usize>().unwrap()).collect();
-        Ok(Coordinate(n[0], n[1]))
+        Coordinate::parse(1, Span::line(s))
     }
 }
 
+impl Coordinate {
+    /// Parses `x,y`, where both are less than [`FLOOR_WIDTH`].
+    fn parse(line: usize, coordinate: Span) -> Result<Coordinate, ParseError> {
+        let (x, y) = coordinate
+            .split_once(",")
+            .ok_or_else(|| ParseError::at(line, coordinate, "expected a coordinate like 3,4"))?;
+
+        let axis = |n: Span| {
+            let n = n.trim();
+            let value = parse_number::<usize>(line, n)?;
+            if value >= FLOOR_WIDTH {
+                return Err(ParseError::at(
+                    line,
+                    n,
+                    &format!("expected a coordinate from 0 to {}", FLOOR_WIDTH - 1),
+                ));
+            }
+            Ok(value)
+        };
+        Ok(Coordinate(axis(x)?, axis(y)?))
+    }
+}
+
+/// A horizontal, vertical or 45 degree diagonal line of vents, from its end with the least `x`.
 #[derive(Debug, Copy, Clone)]
-struct Range(Coordinate, Coordinate);
+pub struct Range(Coordinate, Coordinate);
 
 impl Range {
-    fn from(c1: Coordinate, c2: Coordinate) -> Range {
+    pub fn from(c1: Coordinate, c2: Coordinate) -> Range {
         let mut range = Range(c1, c2);
         // same x
         if c1.0 == c2.0 {
@@ -104,7 +148,8 @@ impl Range {
         Range(self.1, self.0)
     }
 
-    fn locations(&self) -> Vec<Coordinate> {
+    /// Every point on the line, including both ends.
+    pub fn locations(&self) -> Vec<Coordinate> {
         let mut coordinates = vec![];
 
         if self.is_cardinal() {
@@ -136,31 +181,37 @@ impl Range {
         coordinates
     }
 
-    fn is_cardinal(&self) -> bool {
+    /// Whether the line is horizontal or vertical.
+    pub fn is_cardinal(&self) -> bool {
         self.0 .0 == self.1 .0 || self.0 .1 == self.1 .1
     }
 }
 
-fn parse_input(lines: Lines, width: usize, use_diagonals: bool) -> SeaFloor {
-    let mut sea_floor = SeaFloor(vec![vec![0; width]; width]);
-
-    for vent in lines {
-        let coords: Vec<Coordinate> = vent
-            .split(" -> ")
-            .map(|coord| coord.parse::<Coordinate>().unwrap())
-            .collect();
-
-        sea_floor.map_vent(Range::from(coords[0], coords[1]), use_diagonals);
-    }
-
-    sea_floor
+/// One `x1,y1 -> x2,y2` vent per line.
+pub fn parse_vents(lines: Lines) -> Result<Vec<Range>, ParseError> {
+    lines
+        .enumerate()
+        .map(|(i, vent)| {
+            let line = i + 1;
+            let vent = Span::line(vent);
+            let (from, to) = vent
+                .split_once(" -> ")
+                .ok_or_else(|| ParseError::at(line, vent, "expected a vent like 0,9 -> 5,9"))?;
+
+            Ok(Range::from(
+                Coordinate::parse(line, from)?,
+                Coordinate::parse(line, to)?,
+            ))
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use crate::day5::{parse_input, SeaFloor};
+    use crate::day5::{parse_vents, SeaFloor};
+    use crate::error::ParseError;
 
     #[test]
     fn it_parses_test_input() {
@@ -169,7 +220,7 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let sea_floor = parse_input(lines.clone(), 10, false);
+        let sea_floor = SeaFloor::map(&parse_vents(lines).unwrap(), 10, false);
         let expected = SeaFloor(vec![
             vec![0, 0, 0, 0, 0, 0, 0, 1, 0, 0],
             vec![0, 0, 1, 0, 0, 0, 0, 1, 0, 0],
@@ -193,7 +244,7 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let sea_floor = parse_input(lines.clone(), 10, true);
+        let sea_floor = SeaFloor::map(&parse_vents(lines).unwrap(), 10, true);
         let expected = SeaFloor(vec![
             vec![1, 0, 1, 0, 0, 0, 0, 1, 1, 0],
             vec![0, 1, 1, 1, 0, 0, 0, 2, 0, 0],
@@ -212,6 +263,30 @@ mod tests {
         assert_eq!(sea_floor, expected);
     }
 
+    #[test]
+    fn it_reports_bad_vents() {
+        let err = parse_vents("0,9 -> 5,9\n8,0 => 0,8".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(2, 1, "8,0 => 0,8", "expected a vent like 0,9 -> 5,9")
+        );
+
+        let err = parse_vents("0,9 -> 5,y".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 10, "y", "expected a number"));
+
+        let err = parse_vents("0,9 -> 59".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(1, 8, "59", "expected a coordinate like 3,4")
+        );
+
+        let err = parse_vents("0,999 -> 0,1000".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(1, 12, "1000", "expected a coordinate from 0 to 999")
+        );
+    }
+
     #[test]
     fn part1_works() {
         let part1_file = "src/day5/test.txt";
@@ -220,7 +295,7 @@ mod tests {
             fs::read_to_string(part1_file).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let sea_floor = parse_input(lines.clone(), 10, false);
+        let sea_floor = SeaFloor::map(&parse_vents(lines).unwrap(), 10, false);
 
         let dangerous_areas = sea_floor.0.iter().flatten().filter(|&c| *c >= 2u32).count();
         assert_eq!(dangerous_areas, 5);
@@ -234,7 +309,7 @@ mod tests {
             fs::read_to_string(part2_file).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let sea_floor = parse_input(lines.clone(), 10, true);
+        let sea_floor = SeaFloor::map(&parse_vents(lines).unwrap(), 10, true);
 
         let dangerous_areas = sea_floor.0.iter().flatten().filter(|&c| *c >= 2u32).count();
         assert_eq!(dangerous_areas, 12);
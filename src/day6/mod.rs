@@ -1,28 +1,43 @@
 use std::fmt::{Debug, Display, Formatter};
-use std::fs;
 use std::str::Lines;
 
-pub fn run() {
-    let file = "src/day6/input.txt";
+use crate::error::{parse_number, ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
 
-    let contents = fs::read_to_string(file).expect("Something went wrong reading the file");
+/// Lanternfish: count the fish after 80 and 256 days of reproducing.
+pub struct Day6;
 
-    let lines = contents.lines();
-    let mut sea = parse_input(lines.clone());
+impl Solution for Day6 {
+    const DAY: u8 = 6;
 
-    for _ in 0u32..80 {
-        sea.day();
+    type Input = Sea;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input.lines())
     }
-    println!("part 1: {}", sea.population());
 
-    for _ in 80u32..256 {
-        sea.day();
+    fn part1(&self, sea: &Self::Input) -> Result<Answer, SolveError> {
+        let mut sea = sea.clone();
+        for _ in 0u32..80 {
+            sea.day();
+        }
+        Ok(sea.population().into())
+    }
+
+    fn part2(&self, sea: &Self::Input) -> Result<Answer, SolveError> {
+        let mut sea = sea.clone();
+        for _ in 0u32..256 {
+            sea.day();
+        }
+        Ok(sea.population().into())
     }
-    println!("part 2: {}", sea.population());
 }
 
+/// How many lanternfish have each timer value, 0 to 8.
+///
+/// [`Sea::from`] panics on a timer above 8; [`parse_input`] reports one as an error.
 #[derive(Debug, Clone, PartialEq)]
-struct Sea(Vec<u64>);
+pub struct Sea(Vec<u64>);
 
 impl From<Vec<u32>> for Sea {
     fn from(fish: Vec<u32>) -> Sea {
@@ -36,13 +51,21 @@ impl From<Vec<u32>> for Sea {
     }
 }
 
+impl Default for Sea {
+    fn default() -> Sea {
+        Sea::new()
+    }
+}
+
 impl Sea {
-    fn new() -> Sea {
+    /// An empty sea.
+    pub fn new() -> Sea {
         // 0-8 are valid ages
         Sea(vec![0u64; 9])
     }
 
-    fn day(&mut self) {
+    /// Advances one day: every timer counts down, and fish at 0 reset to 6 and spawn a fish at 8.
+    pub fn day(&mut self) {
         // remove the 0s
         let (&reproducing, rest) = self.0.split_first().unwrap();
         self.0 = rest.to_vec();
@@ -57,7 +80,7 @@ impl Sea {
         self.0[6] += reproducing;
     }
 
-    fn population(&self) -> u64 {
+    pub fn population(&self) -> u64 {
         let mut population = 0u64;
         for age in self.0.iter() {
             population += age;
@@ -76,15 +99,24 @@ impl Display for Sea {
     }
 }
 
-fn parse_input(mut lines: Lines) -> Sea {
-    Sea::from(
-        lines
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect::<Vec<u32>>(),
-    )
+/// A single `,` separated line of fish timers, each from 0 to 8.
+pub fn parse_input(mut lines: Lines) -> Result<Sea, ParseError> {
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::end_of_input(1, "expected a list of fish timers"))?;
+
+    let timers = Span::line(line)
+        .split(',')
+        .into_iter()
+        .map(|timer| {
+            let timer = timer.trim();
+            match parse_number::<u32>(1, timer)? {
+                t if t <= 8 => Ok(t),
+                _ => Err(ParseError::at(1, timer, "expected a timer from 0 to 8")),
+            }
+        })
+        .collect::<Result<Vec<u32>, ParseError>>()?;
+    Ok(Sea::from(timers))
 }
 
 #[cfg(test)]
@@ -92,6 +124,7 @@ mod tests {
     use std::fs;
 
     use crate::day6::{parse_input, Sea};
+    use crate::error::ParseError;
 
     #[test]
     fn it_parses_test_input() {
@@ -100,7 +133,7 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let sea = parse_input(lines.clone());
+        let sea = parse_input(lines.clone()).unwrap();
         let expected = Sea::from(vec![3, 4, 3, 1, 2]);
         println!("{}", sea);
         println!("{}", expected);
@@ -108,6 +141,24 @@ mod tests {
         assert_eq!(sea, expected);
     }
 
+    #[test]
+    fn it_reports_bad_fish() {
+        let err = parse_input("3,4,-3".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 5, "-3", "expected a number"));
+
+        let err = parse_input("3,4,9".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(1, 5, "9", "expected a timer from 0 to 8")
+        );
+
+        let err = parse_input("".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(1, 1, "", "expected a list of fish timers")
+        );
+    }
+
     #[test]
     fn fishes_decrement() {
         let mut sea = Sea::new();
@@ -140,7 +191,7 @@ mod tests {
             fs::read_to_string(part1_file).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let mut sea = parse_input(lines.clone());
+        let mut sea = parse_input(lines.clone()).unwrap();
 
         assert_eq!(sea.population(), 5);
         sea.day();
@@ -163,7 +214,7 @@ mod tests {
             fs::read_to_string(part2_file).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let mut sea = parse_input(lines.clone());
+        let mut sea = parse_input(lines.clone()).unwrap();
 
         for _i in 0u32..256 {
             sea.day();
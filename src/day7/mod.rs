@@ -1,23 +1,43 @@
 use std::fmt::Debug;
-use std::fs;
 use std::str::Lines;
 
-pub fn run() {
-    let file = "src/day7/input.txt";
+use crate::error::{parse_number, ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
 
-    let contents = fs::read_to_string(file).expect("Something went wrong reading the file");
+/// The Treachery of Whales: line the crabs up for the least fuel.
+pub struct Day7;
 
-    let lines = contents.lines();
-    let crabs = parse_input(lines.clone());
-    let maneuver = crabs.find_simple_maneuver();
-    println!("part 1: {}", maneuver.1);
+/// The furthest position a crab can start from. Every position up to the furthest crab is
+/// tried, so this keeps the search quick.
+pub const MAX_POSITION: u32 = 9999;
 
-    let maneuver = crabs.find_better_maneuver();
-    println!("part 2: {}", maneuver.1);
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Crabs;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input.lines())
+    }
+
+    fn part1(&self, crabs: &Self::Input) -> Result<Answer, SolveError> {
+        let maneuver = crabs.find_simple_maneuver().ok_or_else(fuel_overflow)?;
+        Ok(maneuver.1.into())
+    }
+
+    fn part2(&self, crabs: &Self::Input) -> Result<Answer, SolveError> {
+        let maneuver = crabs.find_better_maneuver().ok_or_else(fuel_overflow)?;
+        Ok(maneuver.1.into())
+    }
+}
+
+fn fuel_overflow() -> SolveError {
+    SolveError::NoAnswer("the fuel needed overflowed".to_string())
 }
 
+/// The horizontal position of each crab submarine.
 #[derive(Debug, Clone, PartialEq)]
-struct Crabs(Vec<u32>);
+pub struct Crabs(Vec<u32>);
 
 impl From<Vec<u32>> for Crabs {
     fn from(crabs: Vec<u32>) -> Crabs {
@@ -26,63 +46,82 @@ impl From<Vec<u32>> for Crabs {
 }
 
 impl Crabs {
-    fn simple_fuel_cost(&self, direction: u32) -> u32 {
-        self.0
-            .iter()
-            .map(|&c| (c as i32 - direction as i32).abs() as u32)
-            .sum()
+    /// Fuel to move every crab to `direction` at one unit of fuel per step, or `None` if it
+    /// overflows.
+    pub fn simple_fuel_cost(&self, direction: u32) -> Option<u64> {
+        self.0.iter().try_fold(0u64, |total, &c| {
+            total.checked_add((c as i64 - direction as i64).unsigned_abs())
+        })
     }
 
-    fn find_simple_maneuver(&self) -> (u32, u32) {
+    /// The cheapest position and its fuel using [`Crabs::simple_fuel_cost`], or `None` if the
+    /// fuel overflows. Panics without crabs.
+    pub fn find_simple_maneuver(&self) -> Option<(u32, u64)> {
         let max_position = *self.0.iter().max().unwrap();
 
-        let mut least: (u32, u32) = (0, self.simple_fuel_cost(0));
+        let mut least: (u32, u64) = (0, self.simple_fuel_cost(0)?);
 
         for i in 0..max_position {
-            if self.simple_fuel_cost(i) < least.1 {
-                least = (i, self.simple_fuel_cost(i));
+            let cost = self.simple_fuel_cost(i)?;
+            if cost < least.1 {
+                least = (i, cost);
             }
         }
 
         //println!("{:?}", least);
-        least
+        Some(least)
     }
 
-    fn find_better_maneuver(&self) -> (u32, u32) {
+    /// The cheapest position and its fuel using [`Crabs::better_fuel_cost`], or `None` if the
+    /// fuel overflows. Panics without crabs.
+    pub fn find_better_maneuver(&self) -> Option<(u32, u64)> {
         let max_position = *self.0.iter().max().unwrap();
-        let mut least: (u32, u32) = (0, self.better_fuel_cost(0));
+        let mut least: (u32, u64) = (0, self.better_fuel_cost(0)?);
 
         for i in 0..max_position {
-            if self.better_fuel_cost(i) < least.1 {
-                least = (i, self.better_fuel_cost(i));
+            let cost = self.better_fuel_cost(i)?;
+            if cost < least.1 {
+                least = (i, cost);
             }
         }
 
-        least
+        Some(least)
     }
 
-    fn better_fuel_cost(&self, direction: u32) -> u32 {
-        self.0
-            .iter()
-            .map(|&c| {
-                let num_steps = (c as i32 - direction as i32).abs() as u32;
-                // let cost = num_steps * (num_steps + 1)/2;
-                //println!("c: {} direction: {} num_steps: {} cost: {}", c, direction, num_steps, cost);
-                num_steps * (num_steps + 1) / 2
-            })
-            .sum()
+    /// Fuel to move every crab to `direction` when each step costs one more than the last, or
+    /// `None` if it overflows.
+    pub fn better_fuel_cost(&self, direction: u32) -> Option<u64> {
+        self.0.iter().try_fold(0u64, |total, &c| {
+            let num_steps = (c as i64 - direction as i64).unsigned_abs();
+            // let cost = num_steps * (num_steps + 1)/2;
+            //println!("c: {} direction: {} num_steps: {} cost: {}", c, direction, num_steps, cost);
+            total.checked_add(num_steps.checked_mul(num_steps + 1)? / 2)
+        })
     }
 }
 
-fn parse_input(mut lines: Lines) -> Crabs {
-    Crabs::from(
-        lines
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect::<Vec<u32>>(),
-    )
+/// A single `,` separated line of crab positions, each from 0 to [`MAX_POSITION`].
+pub fn parse_input(mut lines: Lines) -> Result<Crabs, ParseError> {
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::end_of_input(1, "expected a list of crab positions"))?;
+
+    let crabs = Span::line(line)
+        .split(',')
+        .into_iter()
+        .map(|crab| {
+            let crab = crab.trim();
+            match parse_number::<u32>(1, crab)? {
+                c if c <= MAX_POSITION => Ok(c),
+                _ => Err(ParseError::at(
+                    1,
+                    crab,
+                    &format!("expected a position from 0 to {}", MAX_POSITION),
+                )),
+            }
+        })
+        .collect::<Result<Vec<u32>, ParseError>>()?;
+    Ok(Crabs::from(crabs))
 }
 
 #[cfg(test)]
@@ -90,6 +129,7 @@ mod tests {
     use std::fs;
 
     use crate::day7::{parse_input, Crabs};
+    use crate::error::ParseError;
 
     #[test]
     fn it_parses_test_input() {
@@ -98,7 +138,7 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let crabs = parse_input(lines.clone());
+        let crabs = parse_input(lines.clone()).unwrap();
         let expected = Crabs::from(vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
         println!("{:?}", crabs);
         println!("{:?}", expected);
@@ -106,20 +146,46 @@ mod tests {
         assert_eq!(crabs, expected);
     }
 
+    #[test]
+    fn it_reports_bad_crabs() {
+        let err = parse_input("16,1,,2".lines()).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 6, "", "expected a number"));
+
+        let err = parse_input("0,100000,4000000000".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(1, 3, "100000", "expected a position from 0 to 9999")
+        );
+    }
+
+    #[test]
+    fn fuel_costs_dont_wrap() {
+        // past i32, where the distance used to wrap
+        let crabs = Crabs::from(vec![0, 4_000_000_000]);
+        assert_eq!(crabs.simple_fuel_cost(0), Some(4_000_000_000));
+        assert_eq!(
+            crabs.better_fuel_cost(0),
+            Some(4_000_000_000 * 4_000_000_001 / 2)
+        );
+
+        let crabs = Crabs::from(vec![u32::MAX; 3]);
+        assert_eq!(crabs.better_fuel_cost(0), None);
+    }
+
     #[test]
     fn crabs_calculate_fuel_cost() {
         let crabs = Crabs::from(vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
-        assert_eq!(crabs.simple_fuel_cost(2), 37);
-        assert_eq!(crabs.simple_fuel_cost(1), 41);
-        assert_eq!(crabs.simple_fuel_cost(3), 39);
-        assert_eq!(crabs.simple_fuel_cost(10), 71);
+        assert_eq!(crabs.simple_fuel_cost(2), Some(37));
+        assert_eq!(crabs.simple_fuel_cost(1), Some(41));
+        assert_eq!(crabs.simple_fuel_cost(3), Some(39));
+        assert_eq!(crabs.simple_fuel_cost(10), Some(71));
     }
 
     #[test]
     fn crabs_calculate_better_fuel_cost() {
         let crabs = Crabs::from(vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
-        assert_eq!(crabs.better_fuel_cost(2), 206);
-        assert_eq!(crabs.better_fuel_cost(5), 168);
+        assert_eq!(crabs.better_fuel_cost(2), Some(206));
+        assert_eq!(crabs.better_fuel_cost(5), Some(168));
     }
 
     #[test]
@@ -129,9 +195,9 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let crabs = parse_input(lines.clone());
+        let crabs = parse_input(lines.clone()).unwrap();
         let maneuver = crabs.find_simple_maneuver();
-        assert_eq!(maneuver, (2, 37));
+        assert_eq!(maneuver, Some((2, 37)));
     }
 
     #[test]
@@ -141,8 +207,8 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
 
         let lines = contents.lines();
-        let crabs = parse_input(lines.clone());
+        let crabs = parse_input(lines.clone()).unwrap();
         let maneuver = crabs.find_better_maneuver();
-        assert_eq!(maneuver, (5, 168));
+        assert_eq!(maneuver, Some((5, 168)));
     }
 }
@@ -1,59 +1,105 @@
 use std::collections::HashMap;
+use std::error::Error;
 // use std::fmt::Debug;
-use std::fs;
+use std::fmt::{Display, Formatter};
 use std::str::Lines;
 
 use itertools::Itertools;
 
-pub fn run() {
-    let file = "src/day8/input.txt";
+use crate::error::{ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
 
-    let contents = fs::read_to_string(file).expect("Something went wrong reading the file");
+/// Seven Segment Search: decode the scrambled wiring of each display.
+pub struct Day8;
 
-    let lines = contents.lines();
-    let problems = parse_input(lines.clone());
-    let mut total = 0;
-    problems.clone().into_iter().for_each(|(_q, a)| {
-        a.split_whitespace().for_each(|digit| {
-            match digit.len() {
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<(String, String)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        Ok(parse_input(input.lines())?
+            .into_iter()
+            .map(|(q, a)| (q.to_string(), a.to_string()))
+            .collect())
+    }
+
+    fn part1(&self, problems: &Self::Input) -> Result<Answer, SolveError> {
+        let mut total = 0u32;
+        problems.iter().for_each(|(_q, a)| {
+            a.split_whitespace().for_each(|digit| match digit.len() {
                 2 | 3 | 4 | 7 => total += 1,
                 _ => {}
-            }
-        })
-    });
-
-    println!("part 1: {}", total);
-
-
-    let mut total = 0;
-    problems.into_iter().for_each(|(q, a)| {
-        //println!("problem: {} | {}", q, a);
-
-        let mapping = get_mapping(q);
-        //println!("mapping: {:?}", mapping);
-
-
-        let mut answer = String::new();
-        a.split_whitespace()
-            .map(|s| s.chars().sorted().collect::<String>())
-            .for_each(|number| {
-                let digit = mapping.iter().find_map(|(&digit, pattern)| {
-                    if pattern.clone() == number {
-                        Some(digit)
-                    } else {
-                        None
-                    }
-                }).unwrap();
-                answer.push(digit);
-            });
-        //println!("{}", answer);
-        total += answer.parse::<u32>().unwrap();
-    });
-    println!("part 2: {}", total);
+            })
+        });
+
+        Ok(total.into())
+    }
+
+    fn part2(&self, problems: &Self::Input) -> Result<Answer, SolveError> {
+        let mut total = 0u32;
+        for (q, a) in problems.iter() {
+            total += decode(q, a)?;
+        }
+
+        Ok(total.into())
+    }
+}
+
+/// Patterns that no wiring of the seven segments would show as the ten digits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WiringError {
+    pub patterns: String,
+}
+
+impl Display for WiringError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "no wiring of the segments shows '{}' as the ten digits",
+            self.patterns
+        )
+    }
+}
+
+impl Error for WiringError {}
+
+impl From<WiringError> for SolveError {
+    fn from(e: WiringError) -> SolveError {
+        SolveError::NoAnswer(e.to_string())
+    }
+}
+
+/// The number shown on a display, from its ten unique `patterns` and its four `output` digits.
+pub fn decode(patterns: &str, output: &str) -> Result<u32, WiringError> {
+    let mapping = get_mapping(patterns)?;
+
+    let mut answer = 0;
+    for number in output
+        .split_whitespace()
+        .map(|s| s.chars().sorted().collect::<String>())
+    {
+        let digit = mapping
+            .iter()
+            .find_map(|(&digit, pattern)| {
+                if pattern.clone() == number {
+                    digit.to_digit(10)
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| WiringError {
+                patterns: patterns.to_string(),
+            })?;
+        answer = answer * 10 + digit;
+    }
+
+    Ok(answer)
 }
 
 fn match_chars(digits: &[String], matches: &str) -> Vec<String> {
-    digits.to_owned()
+    digits
+        .to_owned()
         .into_iter()
         .filter(|digit| {
             let matches_chars = matches.chars().collect_vec();
@@ -64,16 +110,20 @@ fn match_chars(digits: &[String], matches: &str) -> Vec<String> {
                 }
             }
             true
-        }).collect_vec()
+        })
+        .collect_vec()
 }
 
-fn get_mapping(q: &str) -> HashMap<char, String> {
+/// Which segment pattern, with its wires sorted, shows each digit.
+pub fn get_mapping(q: &str) -> Result<HashMap<char, String>, WiringError> {
+    let err = || WiringError {
+        patterns: q.to_string(),
+    };
     let mut mapping: HashMap<char, String> = HashMap::new();
 
-    let digits = q.split_whitespace()
-        .map(|digit| digit.chars()
-            .sorted()
-            .collect::<String>())
+    let digits = q
+        .split_whitespace()
+        .map(|digit| digit.chars().sorted().collect::<String>())
         .collect::<Vec<String>>();
 
     //println!("Digits : {:?}", digits);
@@ -93,7 +143,7 @@ fn get_mapping(q: &str) -> HashMap<char, String> {
     //println!("first 4: {:?}", mapping);
 
     // 0 is six segments, contains 7 and left from 4
-    let seven = mapping.get(&'7').unwrap().clone();
+    let seven = mapping.get(&'7').ok_or_else(err)?.clone();
 
     // contains 0 3 9
     let mut matches_seven = match_chars(&digits, &seven);
@@ -105,7 +155,7 @@ fn get_mapping(q: &str) -> HashMap<char, String> {
 
     //println!("matches 7: {:?}", matches_seven);
 
-    for (index, digit) in matches_seven.clone().iter().enumerate() {
+    for (index, digit) in matches_seven.clone().iter().enumerate().rev() {
         if digit.len() == 5 {
             mapping.insert('3', digit.clone());
             matches_seven.remove(index);
@@ -116,37 +166,40 @@ fn get_mapping(q: &str) -> HashMap<char, String> {
     // println!("matches 7: {:?}", matches_seven);
 
     // 9 matches 3
-    let three= mapping.get(&'3').unwrap().clone();
+    let three = mapping.get(&'3').ok_or_else(err)?.clone();
     let nine_matches_3 = match_chars(&matches_seven, &three);
 
     // println!("nine: {:?}", nine_matches_3);
-    let nine = nine_matches_3.first().unwrap().clone();
+    let nine = nine_matches_3.first().ok_or_else(err)?.clone();
     mapping.insert('9', nine.clone());
 
     // 0 is left
-    let zero = matches_seven.clone().into_iter()
-        .filter(|digit| {
-            digit.clone() != nine
-        })
+    let zero = matches_seven
+        .clone()
+        .into_iter()
+        .filter(|digit| digit.clone() != nine)
         .collect::<Vec<String>>()
         .pop()
-        .unwrap();
+        .ok_or_else(err)?;
     mapping.insert('0', zero);
 
     //println!("add 3 9 0: {:?}", mapping);
 
     // got 0134789
     // need 2, 5, 6
-    let four = mapping.get(&'4').unwrap().clone();
+    let four = mapping.get(&'4').ok_or_else(err)?.clone();
     let mut remaining = digits;
     for known in mapping.values() {
-        remaining = remaining.into_iter()
+        remaining = remaining
+            .into_iter()
             .filter(|digit| digit.clone() != known.clone())
             .collect::<Vec<String>>();
     }
 
     //println!("remaining: {:?}", remaining);
-    assert_eq!(remaining.len(), 3);
+    if remaining.len() != 3 {
+        return Err(err());
+    }
 
     for (index, digit) in remaining.clone().iter().enumerate().rev() {
         if digit.len() == 6 {
@@ -154,9 +207,11 @@ fn get_mapping(q: &str) -> HashMap<char, String> {
             remaining.remove(index);
         }
     }
-    assert_eq!(remaining.len(), 2);
-    let a = remaining.get(0).unwrap();
-    let b = remaining.get(1).unwrap();
+    if remaining.len() != 2 {
+        return Err(err());
+    }
+    let a = &remaining[0];
+    let b = &remaining[1];
     let mut a_missing_in_four = 4;
     for c in four.chars() {
         if a.contains(c) {
@@ -176,25 +231,138 @@ fn get_mapping(q: &str) -> HashMap<char, String> {
     }
 
     // let it be known I hated this and feel dumb
-    mapping
+    if is_wiring(&mapping) {
+        Ok(mapping)
+    } else {
+        Err(err())
+    }
 }
 
+/// The segments of each digit on an unscrambled display.
+const SEGMENTS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+/// Whether `mapping` shows all ten digits through one rewiring of the segments.
+///
+/// Each segment is lit by its own set of digits, so a mapping is a rewiring exactly when each
+/// wire is lit by the set of digits of a different segment.
+fn is_wiring(mapping: &HashMap<char, String>) -> bool {
+    let lit_by = |patterns: &[&str], wire: char| {
+        (0..10)
+            .filter(|&digit| patterns[digit].contains(wire))
+            .map(|digit| 1 << digit)
+            .sum::<u16>()
+    };
+    let patterns = ('0'..='9')
+        .map(|digit| mapping.get(&digit).map(String::as_str))
+        .collect::<Option<Vec<&str>>>();
+
+    match patterns {
+        Some(patterns) => {
+            let wires = ('a'..='g').map(|wire| lit_by(&patterns, wire)).sorted();
+            let segments = ('a'..='g')
+                .map(|segment| lit_by(&SEGMENTS, segment))
+                .sorted();
+            wires.eq(segments)
+        }
+        None => false,
+    }
+}
 
-fn parse_input(mut lines: Lines) -> Vec<(&str, &str)> {
+/// One `patterns | output` display per line: the ten digits' segment patterns, then four of them
+/// again as the output.
+pub fn parse_input(lines: Lines) -> Result<Vec<(&str, &str)>, ParseError> {
     let mut problems = vec![];
-    loop {
-        let problem = lines.next();
-        if problem.is_none() {
-            break;
+    for (i, problem) in lines.enumerate() {
+        let problem = Span::line(problem);
+        let (question, answer) = problem.split_once("|").ok_or_else(|| {
+            ParseError::at(
+                i + 1,
+                problem,
+                "expected '|' between the patterns and the output",
+            )
+        })?;
+        let (question, answer) = (question.trim(), answer.trim());
+        check_display(i + 1, question, answer)?;
+        problems.push((question.text, answer.text));
+    }
+    Ok(problems)
+}
+
+/// Checks a display can be decoded: one pattern for each digit, so one each with 2, 3, 4 and
+/// 7 segments and three each with 5 and 6, from one wiring of the segments, and output digits
+/// that are among the patterns.
+fn check_display(line: usize, question: Span, answer: Span) -> Result<(), ParseError> {
+    let sorted = |pattern: &str| pattern.chars().sorted().collect::<String>();
+
+    let patterns = question.words();
+    if patterns.len() != 10 {
+        return Err(ParseError::at(line, question, "expected 10 patterns"));
+    }
+
+    let mut lengths = [0; 8];
+    for pattern in patterns.iter() {
+        if pattern.text.chars().any(|c| !('a'..='g').contains(&c)) {
+            return Err(ParseError::at(line, *pattern, "expected segments a to g"));
+        }
+        if sorted(pattern.text).chars().dedup().count() != pattern.text.len() {
+            return Err(ParseError::at(
+                line,
+                *pattern,
+                "expected each segment at most once",
+            ));
         }
 
-        let problem = problem.unwrap();
-        let (question, answer) = problem.split_at(problem.find('|').unwrap());
-        problems.push((question.trim(), answer[1..].trim()));
+        let length = pattern.text.len();
+        let expected = match length {
+            2 | 3 | 4 | 7 => 1,
+            5 | 6 => 3,
+            _ => 0,
+        };
+        if expected == 0 {
+            return Err(ParseError::at(
+                line,
+                *pattern,
+                "expected a pattern of 2 to 7 segments",
+            ));
+        }
+        lengths[length] += 1;
+        if lengths[length] > expected {
+            let message = match expected {
+                1 => format!("expected only 1 pattern of {} segments", length),
+                _ => format!("expected only {} patterns of {} segments", expected, length),
+            };
+            return Err(ParseError::at(line, *pattern, &message));
+        }
     }
-    problems
-}
 
+    if get_mapping(question.text).is_err() {
+        return Err(ParseError::at(
+            line,
+            question,
+            "expected patterns from one wiring of the segments",
+        ));
+    }
+
+    let digits = answer.words();
+    if digits.len() != 4 {
+        return Err(ParseError::at(line, answer, "expected 4 output digits"));
+    }
+    let known = patterns
+        .iter()
+        .map(|pattern| sorted(pattern.text))
+        .collect::<Vec<String>>();
+    if let Some(digit) = digits.iter().find(|d| !known.contains(&sorted(d.text))) {
+        return Err(ParseError::at(
+            line,
+            *digit,
+            "expected one of the ten patterns",
+        ));
+    }
+
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {
@@ -202,7 +370,9 @@ mod tests {
 
     use itertools::Itertools;
 
-    use crate::day8::{get_mapping, parse_input};
+    use crate::day8::{decode, get_mapping, parse_input, Day8, WiringError};
+    use crate::error::ParseError;
+    use crate::solution::{Solution, SolveError};
 
     #[test]
     fn it_parses_test_input() {
@@ -210,18 +380,117 @@ mod tests {
 
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
         let lines = contents.lines();
-        let problems = parse_input(lines.clone());
+        let problems = parse_input(lines.clone()).unwrap();
         assert_eq!(problems.len(), 10);
-        assert_eq!(problems[0].0, "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb");
+        assert_eq!(
+            problems[0].0,
+            "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb"
+        );
         assert_eq!(problems[0].1, "fdgacbe cefdb cefbgd gcbe");
-        assert_eq!(problems[9].0, "gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc");
+        assert_eq!(
+            problems[9].0,
+            "gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc"
+        );
         assert_eq!(problems[9].1, "fgae cfgab fg bagce");
     }
 
+    #[test]
+    fn it_reports_missing_separators() {
+        let err = parse_input("be cfbegad\ncbdgef fdgacbe".lines()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(
+                1,
+                1,
+                "be cfbegad",
+                "expected '|' between the patterns and the output"
+            )
+        );
+    }
+
+    #[test]
+    fn it_reports_undecodable_displays() {
+        let patterns = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab";
+        let err = |display: &str| parse_input(display.lines()).unwrap_err();
+        let display = |patterns: &str| format!("{} | cdfeb fcadb cdfeb cdbaf", patterns);
+
+        assert_eq!(
+            err("ab | cd"),
+            ParseError::new(1, 1, "ab", "expected 10 patterns")
+        );
+        assert_eq!(
+            err(&display(&patterns.replace("dab", "dax"))),
+            ParseError::new(1, 27, "dax", "expected segments a to g")
+        );
+        assert_eq!(
+            err(&display(&patterns.replace("dab", "dad"))),
+            ParseError::new(1, 27, "dad", "expected each segment at most once")
+        );
+        assert_eq!(
+            err(&display(&patterns.replace("dab", "d"))),
+            ParseError::new(1, 27, "d", "expected a pattern of 2 to 7 segments")
+        );
+        assert_eq!(
+            err(&display(&patterns.replace("dab", "da"))),
+            ParseError::new(1, 56, "ab", "expected only 1 pattern of 2 segments")
+        );
+        assert_eq!(
+            err(&format!("{} | cdfeb fcadb cdfeb", patterns)),
+            ParseError::new(1, 62, "cdfeb fcadb cdfeb", "expected 4 output digits")
+        );
+        assert_eq!(
+            err(&format!("{} | cdfeb fcadb cdfeb dc", patterns)),
+            ParseError::new(1, 80, "dc", "expected one of the ten patterns")
+        );
+
+        // the right lengths, but no wiring lights these
+        let scrambled = "ef adg aceg abcdefg abcdg acdfg abefg acdefg abcdef abcdeg";
+        assert_eq!(
+            err(&format!("{} | ef adg abcdg abcdef", scrambled)),
+            ParseError::new(
+                1,
+                1,
+                scrambled,
+                "expected patterns from one wiring of the segments"
+            )
+        );
+    }
+
+    #[test]
+    fn impossible_wirings_have_no_answer() {
+        let scrambled = "ef adg aceg abcdefg abcdg acdfg abefg acdefg abcdef abcdeg";
+        let err = WiringError {
+            patterns: scrambled.to_string(),
+        };
+        assert_eq!(get_mapping(scrambled), Err(err.clone()));
+        assert_eq!(decode(scrambled, "ef adg abcdg abcdef"), Err(err.clone()));
+        assert_eq!(
+            Day8.part2(&vec![(scrambled.to_string(), "ef ef ef ef".to_string())]),
+            Err(SolveError::NoAnswer(err.to_string()))
+        );
+
+        // every way of relabelling the wires still decodes
+        let display = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab";
+        for wires in "abcdefg".chars().permutations(7).step_by(97) {
+            let rewire = |s: &str| {
+                s.chars()
+                    .map(|c| match c {
+                        'a'..='g' => wires[c as usize - 'a' as usize],
+                        c => c,
+                    })
+                    .collect::<String>()
+            };
+            assert_eq!(
+                decode(&rewire(display), &rewire("cdfeb fcadb cdfeb cdbaf")),
+                Ok(5353)
+            );
+        }
+    }
+
     #[test]
     fn it_parses_mappings() {
         let q = "gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc";
-        let mapping = get_mapping(q);
+        let mapping = get_mapping(q).unwrap();
         println!("Mapping: {:?}", mapping);
         assert_eq!(mapping[&'1'], "fg");
         assert_eq!(mapping[&'4'], "aefg");
@@ -235,20 +504,29 @@ mod tests {
         assert_eq!(mapping[&'9'], "abcefg");
     }
 
+    #[test]
+    fn it_decodes_displays() {
+        assert_eq!(
+            decode(
+                "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab",
+                "cdfeb fcadb cdfeb cdbaf"
+            ),
+            Ok(5353)
+        );
+    }
+
     #[test]
     fn part1_works() {
         let filename = "src/day8/test.txt";
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
         let lines = contents.lines();
 
-        let problems = parse_input(lines.clone());
+        let problems = parse_input(lines.clone()).unwrap();
         let mut total = 0;
         problems.into_iter().for_each(|(_q, a)| {
-            a.split_whitespace().for_each(|digit| {
-                match digit.len() {
-                    2 | 3 | 4 | 7 => total += 1,
-                    _ => {}
-                }
+            a.split_whitespace().for_each(|digit| match digit.len() {
+                2 | 3 | 4 | 7 => total += 1,
+                _ => {}
             })
         });
         assert_eq!(total, 26);
@@ -260,26 +538,28 @@ mod tests {
         let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
         let lines = contents.lines();
 
-        let problems = parse_input(lines.clone());
+        let problems = parse_input(lines.clone()).unwrap();
         let mut total = 0;
         problems.into_iter().for_each(|(q, a)| {
             println!("problem: {} | {}", q, a);
 
-            let mapping = get_mapping(q);
+            let mapping = get_mapping(q).unwrap();
             println!("mapping: {:?}", mapping);
 
-
             let mut answer = String::new();
             a.split_whitespace()
                 .map(|s| s.chars().sorted().collect::<String>())
                 .for_each(|number| {
-                    let digit = mapping.iter().find_map(|(&digit, pattern)| {
-                        if pattern.clone() == number {
-                            Some(digit)
-                        } else {
-                            None
-                        }
-                    }).unwrap();
+                    let digit = mapping
+                        .iter()
+                        .find_map(|(&digit, pattern)| {
+                            if pattern.clone() == number {
+                                Some(digit)
+                            } else {
+                                None
+                            }
+                        })
+                        .unwrap();
                     answer.push(digit);
                 });
             println!("{}", answer);
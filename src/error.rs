@@ -0,0 +1,255 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Where and why a puzzle input failed to parse. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, token: &str, message: &str) -> ParseError {
+        ParseError {
+            file: None,
+            line,
+            column,
+            token: token.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// An error pointing at `token` on `line`.
+    pub fn at(line: usize, token: Span, message: &str) -> ParseError {
+        ParseError::new(line, token.column, token.text, message)
+    }
+
+    /// An error for input that stopped before something we needed.
+    pub fn end_of_input(line: usize, message: &str) -> ParseError {
+        ParseError::new(line, 1, "", message)
+    }
+
+    pub fn in_file(mut self, file: &str) -> ParseError {
+        self.file = Some(file.to_string());
+        self
+    }
+
+    /// Moves an error found by parsing a single line (always reported as line 1) to `line`.
+    pub fn on_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+
+    fn describe(&self) -> String {
+        if self.token.is_empty() {
+            self.message.clone()
+        } else {
+            format!("{}, found '{}'", self.message, self.token)
+        }
+    }
+
+    /// Renders the error like a compiler diagnostic, quoting the offending line of `source`.
+    pub fn render(&self, source: &str) -> String {
+        let location = format!(
+            "{}:{}:{}",
+            self.file.as_deref().unwrap_or("<input>"),
+            self.line,
+            self.column
+        );
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        let mut rendered = format!("error: {}\n{}--> {}\n", self.describe(), gutter, location);
+        if let Some(text) = source.lines().nth(self.line.wrapping_sub(1)) {
+            let marker = "^".repeat(self.token.chars().count().max(1));
+            rendered.push_str(&format!("{} |\n", gutter));
+            rendered.push_str(&format!("{} | {}\n", line_number, text));
+            rendered.push_str(&format!(
+                "{} | {}{}\n",
+                gutter,
+                " ".repeat(self.column.saturating_sub(1)),
+                marker
+            ));
+        }
+        rendered
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(f, "{}:{}: {}", self.line, self.column, self.describe())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Part of a line of input and the 1-based character column it starts at. Splitting a span
+/// keeps count of the columns, so errors can point at any piece of a line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span<'a> {
+    pub column: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    /// A whole line.
+    pub fn line(text: &'a str) -> Span<'a> {
+        Span { column: 1, text }
+    }
+
+    /// The part of this span from byte `start` up to byte `end`.
+    pub fn slice(&self, start: usize, end: usize) -> Span<'a> {
+        Span {
+            column: self.column + self.text[..start].chars().count(),
+            text: &self.text[start..end],
+        }
+    }
+
+    /// The column just after the span.
+    pub fn end(&self) -> usize {
+        self.column + self.text.chars().count()
+    }
+
+    pub fn trim(&self) -> Span<'a> {
+        let start = self.text.len() - self.text.trim_start().len();
+        self.slice(start, self.text.trim_end().len().max(start))
+    }
+
+    /// The parts before and after the first `separator`.
+    pub fn split_once(&self, separator: &str) -> Option<(Span<'a>, Span<'a>)> {
+        let at = self.text.find(separator)?;
+        Some((
+            self.slice(0, at),
+            self.slice(at + separator.len(), self.text.len()),
+        ))
+    }
+
+    /// The parts between each `separator`.
+    pub fn split(&self, separator: char) -> Vec<Span<'a>> {
+        let mut parts = vec![];
+        let (mut start, mut start_column) = (0, self.column);
+        for (column, (offset, c)) in self.text.char_indices().enumerate() {
+            if c == separator {
+                parts.push(Span {
+                    column: start_column,
+                    text: &self.text[start..offset],
+                });
+                start = offset + c.len_utf8();
+                start_column = self.column + column + 1;
+            }
+        }
+        parts.push(Span {
+            column: start_column,
+            text: &self.text[start..],
+        });
+        parts
+    }
+
+    /// The whitespace separated words.
+    pub fn words(&self) -> Vec<Span<'a>> {
+        let mut words = vec![];
+        let mut word: Option<(usize, usize)> = None;
+        for (column, (offset, c)) in self.text.char_indices().enumerate() {
+            if !c.is_whitespace() {
+                word.get_or_insert((offset, self.column + column));
+            } else if let Some((start, start_column)) = word.take() {
+                words.push(Span {
+                    column: start_column,
+                    text: &self.text[start..offset],
+                });
+            }
+        }
+        if let Some((start, start_column)) = word {
+            words.push(Span {
+                column: start_column,
+                text: &self.text[start..],
+            });
+        }
+        words
+    }
+}
+
+/// Parses `token`, reporting its position if it isn't a number.
+pub fn parse_number<T: FromStr>(line: usize, token: Span) -> Result<T, ParseError> {
+    token
+        .text
+        .parse::<T>()
+        .map_err(|_| ParseError::at(line, token, "expected a number"))
+}
+
+/// Parses a `,` separated list of numbers such as `3,4,3,1,2`.
+pub fn parse_number_list<T: FromStr>(line: usize, list: Span) -> Result<Vec<T>, ParseError> {
+    list.split(',')
+        .into_iter()
+        .map(|n| parse_number(line, n.trim()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{parse_number_list, ParseError, Span};
+
+    fn span(column: usize, text: &str) -> Span {
+        Span { column, text }
+    }
+
+    #[test]
+    fn spans_count_columns_in_characters() {
+        let line = Span::line("forward 5");
+        assert_eq!(line.words(), vec![span(1, "forward"), span(9, "5")]);
+
+        // 'é' is two bytes but one column
+        let line = Span::line("  é,x -> ö  ");
+        assert_eq!(line.trim(), span(3, "é,x -> ö"));
+        assert_eq!(line.trim().end(), 11);
+        assert_eq!(line.split(','), vec![span(1, "  é"), span(5, "x -> ö  ")]);
+        assert_eq!(
+            line.split_once("->"),
+            Some((span(1, "  é,x "), span(9, " ö  ")))
+        );
+        assert_eq!(
+            line.words(),
+            vec![span(3, "é,x"), span(7, "->"), span(10, "ö")]
+        );
+        assert_eq!(line.split_once("=>"), None);
+        assert_eq!(Span::line("   ").trim(), span(4, ""));
+    }
+
+    #[test]
+    fn it_parses_number_lists() {
+        assert_eq!(
+            parse_number_list::<u32>(1, Span::line("3,4,3,1,2")),
+            Ok(vec![3, 4, 3, 1, 2])
+        );
+
+        let err = parse_number_list::<u32>(1, Span::line("3,4,x,1")).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 5, "x", "expected a number"));
+
+        let err = parse_number_list::<u32>(1, Span::line("é, x")).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 1, "é", "expected a number"));
+        let err = parse_number_list::<u32>(1, Span::line("1,ü, x")).unwrap_err();
+        assert_eq!(err, ParseError::new(1, 3, "ü", "expected a number"));
+    }
+
+    #[test]
+    fn it_renders_diagnostics() {
+        let source = "199\n200\n2o8\n210";
+        let err = ParseError::new(3, 1, "2o8", "expected a number").in_file("day1.txt");
+
+        assert_eq!(
+            err.to_string(),
+            "day1.txt:3:1: expected a number, found '2o8'"
+        );
+        assert_eq!(
+            err.render(source),
+            "error: expected a number, found '2o8'\n --> day1.txt:3:1\n  |\n3 | 2o8\n  | ^^^\n"
+        );
+    }
+}
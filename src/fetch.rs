@@ -0,0 +1,335 @@
+use std::env;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// adventofcode.com session cookie used to download inputs.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Where to download inputs from, for pointing [`HttpFetcher`] at a mirror or a test server.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = "github.com/Darunada/aoc2021";
+
+#[derive(Debug)]
+pub enum FetchError {
+    MissingSession,
+    InvalidUrl(String),
+    Status(u16, String),
+    Io(io::Error),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::MissingSession => write!(
+                f,
+                "set {} to your adventofcode.com session cookie to download it",
+                SESSION_VAR
+            ),
+            FetchError::InvalidUrl(url) => write!(f, "invalid url '{}'", url),
+            FetchError::Status(status, message) => {
+                write!(f, "the server responded with {}", status)?;
+                if !message.is_empty() {
+                    write!(f, ": {}", message)?;
+                }
+                Ok(())
+            }
+            FetchError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> FetchError {
+        FetchError::Io(e)
+    }
+}
+
+/// Downloads a day's puzzle input.
+pub trait Fetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, FetchError>;
+}
+
+/// Fetches `<base_url>/<year>/day/<day>/input` with the session cookie.
+/// `http://` is spoken directly; `https://` goes through `curl`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpFetcher {
+    pub base_url: String,
+    pub session: Option<String>,
+}
+
+impl HttpFetcher {
+    /// Reads the session from `AOC_SESSION` and the base url from `AOC_BASE_URL`.
+    pub fn from_env() -> HttpFetcher {
+        HttpFetcher {
+            base_url: env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+            session: env::var(SESSION_VAR)
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+        }
+    }
+
+    pub fn url(&self, year: u16, day: u8) -> String {
+        format!(
+            "{}/{}/day/{}/input",
+            self.base_url.trim_end_matches('/'),
+            year,
+            day
+        )
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, FetchError> {
+        let session = self.session.as_ref().ok_or(FetchError::MissingSession)?;
+        let url = self.url(year, day);
+        let cookie = format!("session={}", session);
+
+        if url.starts_with("http://") {
+            http_get(&url, &cookie)
+        } else if url.starts_with("https://") {
+            curl_get(&url, &cookie)
+        } else {
+            Err(FetchError::InvalidUrl(url))
+        }
+    }
+}
+
+fn http_get(url: &str, cookie: &str) -> Result<String, FetchError> {
+    let rest = url.strip_prefix("http://").unwrap();
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(FetchError::InvalidUrl(url.to_string()));
+    }
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: {}\r\nConnection: close\r\n\r\n",
+        path, authority, USER_AGENT, cookie
+    )?;
+
+    let mut response = vec![];
+    stream.read_to_end(&mut response)?;
+    parse_response(&response)
+}
+
+/// Splits a raw HTTP/1.1 response into its body, failing on anything but a 200.
+fn parse_response(response: &[u8]) -> Result<String, FetchError> {
+    let invalid = || {
+        FetchError::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "malformed response",
+        ))
+    };
+
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(invalid)?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let body = &response[split + 4..];
+
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|l| l.split_ascii_whitespace().nth(1))
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or_else(invalid)?;
+
+    let mut chunked = false;
+    let mut length = None;
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            } else if name.eq_ignore_ascii_case("content-length") {
+                length = value.parse::<usize>().ok();
+            }
+        }
+    }
+
+    let body = if chunked {
+        dechunk(body).ok_or_else(invalid)?
+    } else {
+        let end = length.unwrap_or(body.len()).min(body.len());
+        body[..end].to_vec()
+    };
+    let body = String::from_utf8(body).map_err(|_| invalid())?;
+
+    if status == 200 {
+        Ok(body)
+    } else {
+        // adventofcode.com explains errors in plain text; anything else is just noise here
+        let message = body.lines().next().unwrap_or("").trim();
+        let message = if message.starts_with('<') {
+            ""
+        } else {
+            message
+        };
+        Err(FetchError::Status(status, message.to_string()))
+    }
+}
+
+fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = vec![];
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+        let size = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+/// The cookie goes in on stdin so the session never shows up in the process list.
+fn curl_get(url: &str, cookie: &str) -> Result<String, FetchError> {
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--header", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("downloading over https needs curl: {}", e),
+            )
+        })?;
+
+    curl.stdin
+        .take()
+        .unwrap()
+        .write_all(format!("Cookie: {}\nUser-Agent: {}\n", cookie, USER_AGENT).as_bytes())?;
+    let output = curl.wait_with_output()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout)
+            .map_err(|e| FetchError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(FetchError::Io(io::Error::new(
+            io::ErrorKind::Other,
+            stderr.trim().to_string(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use crate::fetch::{parse_response, FetchError, Fetcher, HttpFetcher};
+
+    /// Serves one canned response on a local port, returning the base url and the request it received.
+    fn serve_once(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buffer).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        (base_url, server)
+    }
+
+    fn fetcher(base_url: &str, session: Option<&str>) -> HttpFetcher {
+        HttpFetcher {
+            base_url: base_url.to_string(),
+            session: session.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn it_builds_input_urls() {
+        assert_eq!(
+            fetcher("https://adventofcode.com/", None).url(2021, 7),
+            "https://adventofcode.com/2021/day/7/input"
+        );
+    }
+
+    #[test]
+    fn it_downloads_inputs_with_the_session() {
+        let (base_url, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 10\r\n\r\n3,4,3,1,2\n",
+        );
+
+        let input = fetcher(&base_url, Some("abc123")).fetch(2021, 6).unwrap();
+        assert_eq!(input, "3,4,3,1,2\n");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2021/day/6/input HTTP/1.1\r\n"));
+        assert!(request.contains("\r\nCookie: session=abc123\r\n"));
+    }
+
+    #[test]
+    fn it_reports_error_responses() {
+        let (base_url, server) = serve_once(
+            "HTTP/1.1 400 Bad Request\r\nContent-Length: 46\r\n\r\nPuzzle inputs differ by user.  Please log in.\n",
+        );
+
+        let err = fetcher(&base_url, Some("expired"))
+            .fetch(2021, 6)
+            .unwrap_err();
+        server.join().unwrap();
+        assert!(matches!(err, FetchError::Status(400, _)));
+        assert_eq!(
+            err.to_string(),
+            "the server responded with 400: Puzzle inputs differ by user.  Please log in."
+        );
+    }
+
+    #[test]
+    fn it_needs_a_session() {
+        // nothing listens here, so this would fail differently if it tried to connect
+        let err = fetcher("http://127.0.0.1:9", None)
+            .fetch(2021, 6)
+            .unwrap_err();
+        assert!(matches!(err, FetchError::MissingSession));
+    }
+
+    #[test]
+    fn it_decodes_chunked_responses() {
+        let response =
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n3,4,\r\n6\r\n3,1,2\n\r\n0\r\n\r\n";
+        assert_eq!(parse_response(response).unwrap(), "3,4,3,1,2\n");
+        assert!(parse_response(b"not http").is_err());
+    }
+}
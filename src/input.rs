@@ -0,0 +1,154 @@
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::cache::{default_dir, CacheError, InputCache, YEAR};
+use crate::fetch::{Fetcher, HttpFetcher};
+
+/// Directory holding one `day<N>.txt` per day, used instead of the download cache.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    /// The day's input from the cache, downloaded if it isn't there yet.
+    Cached(u8),
+}
+
+impl InputSource {
+    /// `-` means stdin, anything else is a path.
+    pub fn from_arg(arg: &str) -> InputSource {
+        if arg == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(PathBuf::from(arg))
+        }
+    }
+
+    /// Where a day's input lives when no `--input` was given.
+    pub fn default_for(day: u8) -> InputSource {
+        let input_dir = env::var_os(INPUT_DIR_VAR).map(PathBuf::from);
+        default_source(input_dir.as_deref(), day)
+    }
+
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            InputSource::File(path) => fs::read_to_string(path),
+            InputSource::Stdin => {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents)?;
+                Ok(contents)
+            }
+            InputSource::Cached(day) => read_cached(&cache(), *day),
+        }
+    }
+}
+
+/// A failed download means the input isn't there, but a cache that can't be read or written is
+/// its own problem, so that error is passed on as it is.
+fn read_cached<F: Fetcher>(cache: &InputCache<F>, day: u8) -> io::Result<String> {
+    cache.get(day).map_err(|e| match e {
+        CacheError::Io(e) => e,
+        CacheError::Fetch(e) => io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("not cached, and could not download it: {}", e),
+        ),
+    })
+}
+
+fn cache() -> InputCache<HttpFetcher> {
+    InputCache::new(default_dir(), YEAR, HttpFetcher::from_env())
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::Cached(day) => write!(f, "{}", cache().path(*day).display()),
+        }
+    }
+}
+
+fn default_source(input_dir: Option<&Path>, day: u8) -> InputSource {
+    match input_dir {
+        Some(dir) => InputSource::File(dir.join(format!("day{}.txt", day))),
+        None => InputSource::Cached(day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    use crate::cache::InputCache;
+    use crate::fetch::{FetchError, Fetcher};
+    use crate::input::{default_source, read_cached, InputSource};
+
+    struct OfflineFetcher;
+
+    impl Fetcher for OfflineFetcher {
+        fn fetch(&self, _year: u16, _day: u8) -> Result<String, FetchError> {
+            Err(FetchError::MissingSession)
+        }
+    }
+
+    #[test]
+    fn it_reads_dash_as_stdin() {
+        assert_eq!(InputSource::from_arg("-"), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg("inputs/day3.txt"),
+            InputSource::File(PathBuf::from("inputs/day3.txt"))
+        );
+    }
+
+    #[test]
+    fn it_defaults_to_the_cache() {
+        assert_eq!(default_source(None, 4), InputSource::Cached(4));
+    }
+
+    #[test]
+    fn it_uses_the_input_dir_override() {
+        assert_eq!(
+            default_source(Some(Path::new("/home/me/aoc")), 7),
+            InputSource::File(PathBuf::from("/home/me/aoc/day7.txt"))
+        );
+    }
+
+    #[test]
+    fn it_reads_files() {
+        let source = InputSource::from_arg("src/day6/test.txt");
+        assert_eq!(source.read().unwrap().trim(), "3,4,3,1,2");
+        assert!(InputSource::from_arg("src/day6/missing.txt")
+            .read()
+            .is_err());
+    }
+
+    #[test]
+    fn only_failed_downloads_are_missing_inputs() {
+        let dir = env::temp_dir().join(format!("aoc2021-input-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = InputCache::new(dir.clone(), 2021, OfflineFetcher);
+
+        let err = read_cached(&cache, 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err
+            .to_string()
+            .starts_with("not cached, and could not download it: set "));
+
+        fs::create_dir_all(dir.join("2021")).unwrap();
+        fs::write(cache.path(2), b"\xff\n").unwrap();
+        let err = read_cached(&cache, 2).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(!err.to_string().contains("not cached"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt::{Display, Formatter};
+
+use crate::error::ParseError;
+use crate::solution::{Answer, Run};
+
+/// Just enough JSON to report runs. Objects keep their keys in insertion order so output diffs cleanly.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object(fields: Vec<(&str, Json)>) -> Json {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(n: u64) -> Json {
+        Json::Number(n)
+    }
+}
+
+impl From<&ParseError> for Json {
+    fn from(e: &ParseError) -> Json {
+        Json::object(vec![
+            ("file", e.file.clone().map_or(Json::Null, Json::from)),
+            ("line", (e.line as u64).into()),
+            ("column", (e.column as u64).into()),
+            ("token", e.token.as_str().into()),
+            ("message", e.message.as_str().into()),
+        ])
+    }
+}
+
+/// One day's answers. Answers are strings so large numbers survive any JSON parser; `type` says how to read them.
+/// A part without an answer has an `error` saying why instead, and the day's `error` names the first such part.
+/// Timings change from run to run, so they are only included when asked for.
+pub fn run_json(day: u8, run: &Run, time: bool) -> Json {
+    let parts = run
+        .parts
+        .iter()
+        .map(|part| {
+            let mut fields = vec![("part", (part.part as u64).into())];
+            match &part.answer {
+                Ok(answer) => {
+                    fields.push(("answer", answer.to_string().into()));
+                    fields.push(("type", answer_type(answer).into()));
+                }
+                Err(e) => fields.push(("error", e.to_string().into())),
+            }
+            if time {
+                fields.push(("elapsed_ns", (part.elapsed.as_nanos() as u64).into()));
+            }
+            Json::object(fields)
+        })
+        .collect();
+
+    let mut fields = vec![("day", (day as u64).into())];
+    if time {
+        fields.push(("parse_ns", (run.parse.as_nanos() as u64).into()));
+    }
+    fields.push(("parts", Json::Array(parts)));
+    fields.push((
+        "error",
+        run.failure().map_or(Json::Null, |(part, e)| {
+            Json::object(vec![
+                ("part", (part as u64).into()),
+                ("message", e.to_string().into()),
+            ])
+        }),
+    ));
+    Json::object(fields)
+}
+
+/// A day that could not be run, with `error` describing why.
+pub fn error_json(day: u8, error: Json) -> Json {
+    Json::object(vec![
+        ("day", (day as u64).into()),
+        ("parts", Json::Array(vec![])),
+        ("error", error),
+    ])
+}
+
+fn answer_type(answer: &Answer) -> &'static str {
+    match answer {
+        Answer::Unsigned(_) => "unsigned",
+        Answer::Signed(_) => "signed",
+        Answer::Text(_) => "text",
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, s: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::error::ParseError;
+    use crate::json::{error_json, run_json, Json};
+    use crate::solution::{Answer, PartResult, Run, SolveError};
+
+    fn run() -> Run {
+        Run {
+            parse: Duration::from_nanos(40),
+            parts: vec![
+                PartResult {
+                    part: 1,
+                    answer: Ok(Answer::Signed(-7)),
+                    elapsed: Duration::from_nanos(5),
+                },
+                PartResult {
+                    part: 2,
+                    answer: Ok(Answer::Text("ab\"c".to_string())),
+                    elapsed: Duration::from_nanos(6),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn it_escapes_strings() {
+        let json = Json::from("a \"b\"\\\n\u{1}");
+        assert_eq!(json.to_string(), r#""a \"b\"\\\n\u0001""#);
+    }
+
+    #[test]
+    fn it_reports_runs_without_timings() {
+        assert_eq!(
+            run_json(2, &run(), false).to_string(),
+            r#"{"day":2,"parts":[{"part":1,"answer":"-7","type":"signed"},{"part":2,"answer":"ab\"c","type":"text"}],"error":null}"#
+        );
+    }
+
+    #[test]
+    fn it_reports_runs_with_timings() {
+        assert_eq!(
+            run_json(2, &run(), true).to_string(),
+            r#"{"day":2,"parse_ns":40,"parts":[{"part":1,"answer":"-7","type":"signed","elapsed_ns":5},{"part":2,"answer":"ab\"c","type":"text","elapsed_ns":6}],"error":null}"#
+        );
+    }
+
+    #[test]
+    fn it_reports_parts_without_answers() {
+        let mut run = run();
+        run.parts[1].answer = Err(SolveError::NoAnswer("no board won".to_string()));
+        assert_eq!(
+            run_json(4, &run, false).to_string(),
+            r#"{"day":4,"parts":[{"part":1,"answer":"-7","type":"signed"},{"part":2,"error":"no board won"}],"error":{"part":2,"message":"no board won"}}"#
+        );
+    }
+
+    #[test]
+    fn it_reports_errors() {
+        let error = ParseError::new(3, 1, "x", "expected a number").in_file("day1.txt");
+        assert_eq!(
+            error_json(1, (&error).into()).to_string(),
+            r#"{"day":1,"parts":[],"error":{"file":"day1.txt","line":3,"column":1,"token":"x","message":"expected a number"}}"#
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! Advent of Code 2021 puzzle solutions.
+//!
+//! Each `dayN` module implements [`solution::Solution`] for that day's puzzle and exposes the
+//! types it's built from, so they can be reused from benchmarks, tests or other crates.
+//! [`registry::registry`] lists every implemented day.
+
+pub mod answers;
+pub mod bench;
+pub mod cache;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod error;
+pub mod fetch;
+pub mod input;
+pub mod json;
+pub mod registry;
+pub mod scaffold;
+pub mod solution;
@@ -1,34 +1,434 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process;
+
+use aoc2021::answers::{Answers, Verdict};
+use aoc2021::bench::{bench, Baseline};
+use aoc2021::day2::trace::{to_csv, to_svg, trace, Snapshot};
+use aoc2021::day2::{get_moves, Aimed, Plain, SurfacePolicy};
+use aoc2021::day3::{parse_report, LifeSupportRating, PowerConsumption, RulePolicy};
+use aoc2021::input::InputSource;
+use aoc2021::json::{error_json, run_json, Json};
+use aoc2021::registry::registry;
+use aoc2021::scaffold::new_day;
+use aoc2021::solution::{Parts, Runner, SolveError};
+
+use crate::cli::{
+    parse_args, Args, BenchArgs, Command, ExplainArgs, Format, Model, TraceArgs, TraceFormat,
+    VerifyArgs, USAGE,
+};
+
+mod cli;
 
 fn main() {
-    println!("Day 1");
-    day1::run();
+    let days = registry();
+    let available = days.iter().map(|d| d.day()).collect::<Vec<u8>>();
+
+    let command = match parse_args(env::args().skip(1), &available) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Run(args) => run(&days, args),
+        Command::Bench(args) => run_bench(&days, args),
+        Command::Verify(args) => run_verify(&days, args),
+        Command::New(day) => run_new(day),
+        Command::Trace(args) => run_trace(args),
+        Command::Explain(args) => run_explain(args),
+    }
+}
+
+fn run(days: &[Box<dyn Runner>], args: Args) {
+    match args.format {
+        Format::Text => run_text(days, args),
+        Format::Json => run_json_lines(days, args),
+    }
+}
+
+/// Prints each day's answers. A part without an answer is reported in its place, and the
+/// command exits non-zero once every day has run.
+fn run_text(days: &[Box<dyn Runner>], args: Args) {
+    let mut failed = false;
+    for (i, day) in args.days.iter().enumerate() {
+        let runner = find_day(days, *day);
+        let (source, contents) = read_input(*day, &args.input);
+
+        if i > 0 {
+            println!();
+        }
+        println!("Day {}", day);
+        let result = match runner.run(&contents, args.parts) {
+            Ok(result) => result,
+            Err(e) => {
+                eprint!("{}", e.in_file(&source.to_string()).render(&contents));
+                process::exit(1);
+            }
+        };
+
+        if args.time {
+            println!("parse: {:.2?}", result.parse);
+        }
+        for part in result.parts {
+            let answer = match &part.answer {
+                Ok(answer) => answer.to_string(),
+                Err(e) => {
+                    failed = true;
+                    format!("error: {}", e)
+                }
+            };
+            if args.time {
+                println!("part {}: {} ({:.2?})", part.part, answer, part.elapsed);
+            } else {
+                println!("part {}: {}", part.part, answer);
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// One JSON object per line per day. A day that fails to parse or has a part without an answer
+/// is reported, the rest still run, and the command exits non-zero at the end.
+fn run_json_lines(days: &[Box<dyn Runner>], args: Args) {
+    let mut failed = false;
+    for day in args.days.iter() {
+        let runner = find_day(days, *day);
+        let source = match &args.input {
+            Some(source) => source.clone(),
+            None => InputSource::default_for(*day),
+        };
+
+        let result = source
+            .read()
+            .map_err(|e| {
+                Json::object(vec![
+                    ("file", source.to_string().into()),
+                    ("message", format!("could not read input: {}", e).into()),
+                ])
+            })
+            .and_then(|contents| {
+                runner
+                    .run(&contents, args.parts)
+                    .map_err(|e| (&e.in_file(&source.to_string())).into())
+            });
+
+        match result {
+            Ok(result) => {
+                println!("{}", run_json(*day, &result, args.time));
+                failed |= result.failure().is_some();
+            }
+            Err(error) => {
+                println!("{}", error_json(*day, error));
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn run_bench(days: &[Box<dyn Runner>], args: BenchArgs) {
+    let runner = find_day(days, args.day);
+    let (source, contents) = read_input(args.day, &args.input);
+
+    let result = match bench(runner, &contents, args.iterations) {
+        Ok(result) => result,
+        Err(e) => {
+            eprint!("{}", e.in_file(&source.to_string()).render(&contents));
+            process::exit(1);
+        }
+    };
+
+    let comparisons = match &args.baseline {
+        Some(path) => match Baseline::load(path) {
+            Ok(baseline) => baseline.compare(&result, args.threshold),
+            Err(e) => {
+                eprintln!("error: could not read baseline {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        },
+        None => vec![],
+    };
 
-    println!("\nDay 2");
-    day2::run();
+    println!("Day {} ({} iterations)", result.day, result.iterations);
+    for (step, stats) in result.steps.iter() {
+        print!(
+            "{:<6} min {:>10.2?}  median {:>10.2?}  p95 {:>10.2?}",
+            step, stats.min, stats.median, stats.p95
+        );
+        if let Some(comparison) = comparisons.iter().find(|c| &c.step == step) {
+            print!(
+                "  {:+.1}% vs baseline{}",
+                comparison.change_percent,
+                if comparison.regressed {
+                    " REGRESSION"
+                } else {
+                    ""
+                }
+            );
+        }
+        if let Some((_, e)) = result
+            .failures
+            .iter()
+            .find(|(part, _)| step == &format!("part{}", part))
+        {
+            print!("  error: {}", e);
+        }
+        println!();
+    }
 
-    println!("\nDay 3");
-    day3::run();
+    if !result.failures.is_empty() {
+        if args.save_baseline.is_some() {
+            eprintln!("error: not saving a baseline for parts without answers");
+        }
+        process::exit(1);
+    }
 
-    println!("\nDay 4");
-    day4::run();
+    if let Some(path) = &args.save_baseline {
+        let mut baseline = match Baseline::load(path) {
+            Ok(baseline) => baseline,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Baseline::default(),
+            Err(e) => {
+                eprintln!("error: could not read baseline {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        };
+        baseline.record(&result);
+        if let Err(e) = baseline.save(path) {
+            eprintln!("error: could not save baseline {}: {}", path.display(), e);
+            process::exit(1);
+        }
+        println!("saved baseline to {}", path.display());
+    }
 
-    println!("\nDay 5");
-    day5::run();
+    if comparisons.iter().any(|c| c.regressed) {
+        process::exit(1);
+    }
+}
+
+fn run_verify(days: &[Box<dyn Runner>], args: VerifyArgs) {
+    let path = args.answers.unwrap_or_else(Answers::default_path);
+    let mut answers = match Answers::load(&path) {
+        Ok(answers) => answers,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Answers::default(),
+        Err(e) => {
+            eprintln!("error: could not read answers {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    let (mut passed, mut failed, mut missing, mut recorded) = (0, 0, 0, 0);
+    for day in args.days.iter() {
+        let runner = find_day(days, *day);
+        let source = InputSource::default_for(*day);
+        let contents = match source.read() {
+            Ok(contents) => contents,
+            Err(e) => {
+                println!("day {}: could not read input from {}: {}", day, source, e);
+                failed += 1;
+                continue;
+            }
+        };
+        let result = match runner.run(&contents, Parts::Both) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("day {}: {}", day, e.in_file(&source.to_string()));
+                failed += 1;
+                continue;
+            }
+        };
+
+        for part in result.parts {
+            let label = format!("day {} part {}", day, part.part);
+            let answer = match part.answer {
+                Ok(answer) => answer,
+                Err(SolveError::NotImplemented) => {
+                    println!("{}: missing, not implemented yet", label);
+                    missing += 1;
+                    continue;
+                }
+                Err(e) => {
+                    println!("{}: FAIL {}", label, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            match answers.check(*day, part.part, &answer) {
+                Verdict::Pass => {
+                    println!("{}: pass ({})", label, answer);
+                    passed += 1;
+                }
+                Verdict::Fail(expected) => {
+                    println!("{}: FAIL expected {}, got {}", label, expected, answer);
+                    failed += 1;
+                }
+                Verdict::Missing if args.record => {
+                    println!("{}: recorded {}", label, answer);
+                    answers.record(*day, part.part, answer);
+                    recorded += 1;
+                }
+                Verdict::Missing => {
+                    println!("{}: missing, got {}", label, answer);
+                    missing += 1;
+                }
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "{} passed, {} failed, {} missing, {} recorded",
+        passed, failed, missing, recorded
+    );
+
+    if recorded > 0 {
+        if let Err(e) = answers.save(&path) {
+            eprintln!("error: could not save answers {}: {}", path.display(), e);
+            process::exit(1);
+        }
+        println!("saved answers to {}", path.display());
+    }
+
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+fn run_new(day: u8) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    match new_day(root, day) {
+        Ok(written) => {
+            for path in written {
+                println!(
+                    "wrote {}",
+                    path.strip_prefix(root).unwrap_or(&path).display()
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("error: could not create day {}: {}", day, e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Prints day 2's course step by step. A model that fails partway is traced up to the failure,
+/// and the failure is reported once everything is printed.
+fn run_trace(args: TraceArgs) {
+    let (source, contents) = read_input(2, &args.input);
+    let moves = match get_moves(contents.lines()) {
+        Ok(moves) => moves,
+        Err(e) => {
+            eprint!("{}", e.in_file(&source.to_string()).render(&contents));
+            process::exit(1);
+        }
+    };
 
-    println!("\nDay 6");
-    day6::run();
+    let mut traces = vec![];
+    let mut failed = false;
+    for model in args.models {
+        let (name, steps) = match model {
+            Model::Plain => (
+                "plain",
+                trace(Plain, SurfacePolicy::default(), &moves).collect::<Vec<_>>(),
+            ),
+            Model::Aimed => (
+                "aimed",
+                trace(Aimed, SurfacePolicy::default(), &moves).collect::<Vec<_>>(),
+            ),
+        };
+
+        let mut snapshots = vec![];
+        for step in steps {
+            match step {
+                Ok(snapshot) => snapshots.push(snapshot),
+                Err(e) => {
+                    eprintln!(
+                        "error: the {} model stopped after step {}: {}",
+                        name,
+                        snapshots.len(),
+                        e
+                    );
+                    failed = true;
+                }
+            }
+        }
+        traces.push((name, snapshots));
+    }
+
+    let traces = traces
+        .iter()
+        .map(|(name, snapshots)| (*name, snapshots.as_slice()))
+        .collect::<Vec<(&str, &[Snapshot])>>();
+    match args.format {
+        TraceFormat::Csv => print!("{}", to_csv(&traces)),
+        TraceFormat::Svg => print!("{}", to_svg(&traces)),
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+/// Shows how day 3's rates and ratings are worked out, with the puzzle's rules unless a policy
+/// is given. Exits non-zero if any of them has no answer.
+fn run_explain(args: ExplainArgs) {
+    let (source, contents) = read_input(3, &args.input);
+    let report = match parse_report(&contents.lines()) {
+        Ok(report) => report,
+        Err(e) => {
+            eprint!("{}", e.in_file(&source.to_string()).render(&contents));
+            process::exit(1);
+        }
+    };
+
+    let mut explanations =
+        PowerConsumption::explain(&report, args.policy.unwrap_or(RulePolicy::TieToZero));
+    explanations.extend(LifeSupportRating::explain(
+        &report,
+        args.policy.unwrap_or(RulePolicy::TieToOne),
+    ));
+    for (i, explanation) in explanations.iter().enumerate() {
+        match args.format {
+            Format::Text if i > 0 => print!("\n{}", explanation),
+            Format::Text => print!("{}", explanation),
+            Format::Json => println!("{}", explanation.to_json()),
+        }
+    }
+
+    if explanations.iter().any(|e| e.result.is_err()) {
+        process::exit(1);
+    }
+}
+
+fn find_day(days: &[Box<dyn Runner>], day: u8) -> &dyn Runner {
+    days.iter().find(|d| d.day() == day).unwrap().as_ref()
+}
 
-    println!("\nDay 7");
-    day7::run();
+fn read_input(day: u8, input: &Option<InputSource>) -> (InputSource, String) {
+    let source = match input {
+        Some(source) => source.clone(),
+        None => InputSource::default_for(day),
+    };
 
-    println!("\nDay 8");
-    day8::run();
+    match source.read() {
+        Ok(contents) => (source, contents),
+        Err(e) => {
+            eprintln!(
+                "error: could not read input for day {} from {}: {}",
+                day, source, e
+            );
+            process::exit(1);
+        }
+    }
 }
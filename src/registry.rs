@@ -0,0 +1,16 @@
+use crate::solution::Runner;
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8};
+
+/// Every implemented day, in order. `aoc2021 new <day>` registers new days here.
+pub fn registry() -> Vec<Box<dyn Runner>> {
+    vec![
+        Box::new(day1::Day1),
+        Box::new(day2::Day2),
+        Box::new(day3::Day3),
+        Box::new(day4::Day4),
+        Box::new(day5::Day5),
+        Box::new(day6::Day6),
+        Box::new(day7::Day7),
+        Box::new(day8::Day8),
+    ]
+}
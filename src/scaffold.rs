@@ -0,0 +1,333 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Creates `src/day<N>/` with a module skeleton and an empty `test.txt`, and registers
+/// the module in `src/lib.rs` and `src/registry.rs` under `root`. Returns every file it wrote.
+///
+/// Nothing is written if the day already has a directory or a module declaration.
+pub fn new_day(root: &Path, day: u8) -> io::Result<Vec<PathBuf>> {
+    let src = root.join("src");
+    let dir = src.join(format!("day{}", day));
+    let lib_path = src.join("lib.rs");
+    let registry_path = src.join("registry.rs");
+
+    if dir.exists() {
+        return Err(already_exists(format!("{} already exists", dir.display())));
+    }
+
+    let lib = add_module(&fs::read_to_string(&lib_path)?, day).ok_or_else(|| {
+        already_exists(format!("day{} is already in {}", day, lib_path.display()))
+    })?;
+    let registry = register(&fs::read_to_string(&registry_path)?, day).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "could not register day{} in {}",
+                day,
+                registry_path.display()
+            ),
+        )
+    })?;
+
+    fs::create_dir_all(&dir)?;
+    let module = dir.join("mod.rs");
+    let test = dir.join("test.txt");
+    create(&module, &module_template(day))?;
+    create(&test, "")?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&registry_path, registry)?;
+
+    Ok(vec![module, test, lib_path, registry_path])
+}
+
+fn already_exists(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::AlreadyExists, message)
+}
+
+/// Writes `path`, failing rather than replacing it if it exists.
+fn create(path: &Path, contents: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents.as_bytes())
+}
+
+/// Adds `pub mod day<N>;` to the block of module declarations, keeping rustfmt's ordering.
+/// `None` if the module is already declared.
+fn add_module(lib: &str, day: u8) -> Option<String> {
+    let declaration = format!("pub mod day{};", day);
+    let lines = lib.lines().collect::<Vec<&str>>();
+    if lines.contains(&declaration.as_str()) {
+        return None;
+    }
+
+    let first = lines.iter().position(|l| l.starts_with("pub mod "))?;
+    let count = lines[first..]
+        .iter()
+        .take_while(|l| l.starts_with("pub mod "))
+        .count();
+
+    let mut modules = lines[first..first + count].to_vec();
+    modules.push(&declaration);
+    modules.sort_by_key(|m| m.trim_end_matches(';'));
+
+    let mut updated = lines[..first].to_vec();
+    updated.extend(modules);
+    updated.extend(&lines[first + count..]);
+    Some(updated.join("\n") + "\n")
+}
+
+/// Adds the day to the `use crate::{...}` list and appends it to the registry, keeping days in order.
+fn register(registry: &str, day: u8) -> Option<String> {
+    let module = format!("day{}", day);
+    let entry = format!("Box::new({}::Day{}),", module, day);
+
+    let use_start = registry.find("use crate::{")?;
+    let use_end = use_start + registry[use_start..].find("};")? + 2;
+    let mut modules = registry[use_start + "use crate::{".len()..use_end - 2]
+        .split(',')
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<&str>>();
+    if modules.contains(&module.as_str()) {
+        return None;
+    }
+    modules.push(&module);
+    modules.sort_unstable();
+
+    let mut updated = format!(
+        "{}{}{}",
+        &registry[..use_start],
+        use_list(&modules),
+        &registry[use_end..]
+    );
+
+    let vec_start = updated.find("vec![")?;
+    let vec_end = vec_start + updated[vec_start..].find(']')?;
+    let insert_at = updated[vec_start..vec_end]
+        .lines()
+        .filter_map(|line| {
+            let existing = line.trim().strip_prefix("Box::new(day")?;
+            let existing = existing.split("::").next()?.parse::<u8>().ok()?;
+            if existing > day {
+                Some(line)
+            } else {
+                None
+            }
+        })
+        .next()
+        .map(|line| vec_start + updated[vec_start..].find(line).unwrap())
+        .unwrap_or_else(|| vec_start + updated[vec_start..vec_end].rfind('\n').unwrap() + 1);
+    updated.insert_str(insert_at, &format!("        {}\n", entry));
+
+    Some(updated)
+}
+
+/// `use crate::{...};` on one line if it fits, otherwise wrapped the way rustfmt would.
+fn use_list(modules: &[&str]) -> String {
+    let single = format!("use crate::{{{}}};", modules.join(", "));
+    if single.len() <= 100 {
+        return single;
+    }
+
+    let mut wrapped = "use crate::{\n".to_string();
+    let mut line = String::new();
+    for module in modules {
+        if !line.is_empty() && 4 + line.len() + 1 + module.len() + 1 > 100 {
+            wrapped.push_str(&format!("    {}\n", line));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(module);
+        line.push(',');
+    }
+    wrapped.push_str(&format!("    {}\n}};", line));
+    wrapped
+}
+
+fn module_template(day: u8) -> String {
+    TEMPLATE.replace("{day}", &day.to_string())
+}
+
+const TEMPLATE: &str = r#"use std::str::Lines;
+
+use crate::error::{parse_number, ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
+
+/// Day {day}.
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    const DAY: u8 = {day};
+
+    type Input = Vec<u32>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input.lines())
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+        part2(input).map(Answer::from)
+    }
+}
+
+/// One number per line.
+pub fn parse_input(lines: Lines) -> Result<Vec<u32>, ParseError> {
+    lines
+        .enumerate()
+        .map(|(i, line)| parse_number(i + 1, Span::line(line).trim()))
+        .collect()
+}
+
+pub fn part1(_input: &[u32]) -> Result<u32, SolveError> {
+    Err(SolveError::NotImplemented)
+}
+
+pub fn part2(_input: &[u32]) -> Result<u32, SolveError> {
+    Err(SolveError::NotImplemented)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::day{day}::*;
+
+    #[test]
+    #[ignore = "fill in test.txt and the example answer"]
+    fn part1_works() {
+        let filename = "src/day{day}/test.txt";
+
+        let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
+
+        let lines = contents.lines();
+        assert_eq!(part1(&parse_input(lines).unwrap()), Ok(0));
+    }
+
+    #[test]
+    #[ignore = "fill in test.txt and the example answer"]
+    fn part2_works() {
+        let filename = "src/day{day}/test.txt";
+
+        let contents = fs::read_to_string(filename).expect("Something went wrong reading the file");
+
+        let lines = contents.lines();
+        assert_eq!(part2(&parse_input(lines).unwrap()), Ok(0));
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::io;
+    use std::path::PathBuf;
+
+    use crate::scaffold::{add_module, new_day, register};
+
+    const LIB: &str = "//! Docs.\n\npub mod bench;\npub mod day1;\npub mod day2;\npub mod error;\n";
+
+    const REGISTRY: &str = r#"use crate::solution::Runner;
+use crate::{day1, day2};
+
+pub fn registry() -> Vec<Box<dyn Runner>> {
+    vec![
+        Box::new(day1::Day1),
+        Box::new(day2::Day2),
+    ]
+}
+"#;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("aoc2021-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/registry.rs"), REGISTRY).unwrap();
+        root
+    }
+
+    #[test]
+    fn it_declares_modules_in_rustfmt_order() {
+        assert_eq!(
+            add_module(LIB, 10).unwrap(),
+            "//! Docs.\n\npub mod bench;\npub mod day1;\npub mod day10;\npub mod day2;\npub mod error;\n"
+        );
+        assert_eq!(add_module(LIB, 2), None);
+    }
+
+    #[test]
+    fn it_registers_days_in_order() {
+        let registry = register(REGISTRY, 9).unwrap();
+        assert!(registry.contains("use crate::{day1, day2, day9};"));
+        assert!(registry
+            .contains("        Box::new(day2::Day2),\n        Box::new(day9::Day9),\n    ]"));
+
+        let registry = register(&register(REGISTRY, 10).unwrap(), 9).unwrap();
+        assert!(registry.contains("use crate::{day1, day10, day2, day9};"));
+        assert!(registry.contains(
+            "        Box::new(day2::Day2),\n        Box::new(day9::Day9),\n        Box::new(day10::Day10),\n"
+        ));
+
+        assert_eq!(register(REGISTRY, 1), None);
+    }
+
+    #[test]
+    fn it_wraps_long_use_lists() {
+        let mut registry = REGISTRY.to_string();
+        for day in 3..=25 {
+            registry = register(&registry, day).unwrap();
+        }
+        assert!(registry.contains(
+            "use crate::{\n    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,\n    day22, day23, day24, day25, day3, day4, day5, day6, day7, day8, day9,\n};"
+        ));
+        assert!(registry.lines().all(|line| line.len() <= 100));
+        assert!(registry.contains("        Box::new(day25::Day25),\n    ]"));
+    }
+
+    #[test]
+    fn it_creates_a_new_day() {
+        let root = temp_root("new-day");
+
+        let written = new_day(&root, 3).unwrap();
+        assert_eq!(written.len(), 4);
+
+        let module = fs::read_to_string(root.join("src/day3/mod.rs")).unwrap();
+        assert!(module.contains("pub struct Day3;"));
+        assert!(module.contains("const DAY: u8 = 3;"));
+        assert!(module.contains("\"src/day3/test.txt\""));
+        assert!(!module.contains("todo!"));
+        assert!(root.join("src/day3/test.txt").exists());
+
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(lib.contains("pub mod day2;\npub mod day3;\npub mod error;"));
+        let registry = fs::read_to_string(root.join("src/registry.rs")).unwrap();
+        assert!(registry.contains("Box::new(day3::Day3),"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn it_refuses_to_overwrite_a_day() {
+        let root = temp_root("existing-day");
+
+        let err = new_day(&root, 2).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(!root.join("src/day2").exists());
+
+        fs::create_dir_all(root.join("src/day4")).unwrap();
+        let err = new_day(&root, 4).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), LIB);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
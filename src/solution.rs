@@ -0,0 +1,264 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use crate::error::ParseError;
+
+/// Which parts of a day [`Runner::run`] answers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parts {
+    Both,
+    One,
+    Two,
+}
+
+impl Parts {
+    /// Whether part `part`, 1 or 2, is one of these.
+    pub fn includes(&self, part: u8) -> bool {
+        match self {
+            Parts::Both => true,
+            Parts::One => part == 1,
+            Parts::Two => part == 2,
+        }
+    }
+}
+
+/// A part's answer, printed as the puzzle expects it. `From` impls let a part return whatever
+/// integer it computed with `.into()`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Unsigned(u64),
+    /// For puzzles where a position or product can go negative.
+    Signed(i64),
+    /// For answers that aren't numbers, or are too big for 64 bits.
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Unsigned(n) => write!(f, "{}", n),
+            Answer::Signed(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Answer {
+        Answer::Unsigned(n as u64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Answer {
+        Answer::Unsigned(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::Unsigned(n as u64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Answer {
+        Answer::Signed(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+/// Why a part has no answer for an input that parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// The part hasn't been written yet, as in a freshly scaffolded day. Says nothing about
+    /// the input.
+    NotImplemented,
+    /// The part is written and the input is well formed, but the puzzle has no answer for it:
+    /// a tie the rules can't break, a count that overflows, a board that never wins. The
+    /// message says why, in a form that reads after "part N: error: ".
+    NoAnswer(String),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::NotImplemented => write!(f, "not implemented yet"),
+            SolveError::NoAnswer(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// A day's puzzle: parse the input once, then answer each part from the parsed form.
+pub trait Solution {
+    /// The day of December the puzzle is from, 1 to 25.
+    const DAY: u8;
+
+    /// The input as both parts want it.
+    type Input;
+
+    /// Reads the whole puzzle input. Fails with a [`ParseError`] pointing at the line and column
+    /// of the first thing that isn't a valid input: text that doesn't follow the format, or a
+    /// value the puzzle rules out, like a number out of range. Anything it accepts, the parts
+    /// must be able to take without panicking.
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Answers part 1, or says why there's no answer with a [`SolveError`].
+    fn part1(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+
+    /// Answers part 2, or says why there's no answer with a [`SolveError`].
+    fn part2(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+}
+
+/// One part's answer, or why it has none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartResult {
+    pub part: u8,
+    pub answer: Result<Answer, SolveError>,
+    pub elapsed: Duration,
+}
+
+/// The answers from one run of a day, with how long each step took.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Run {
+    pub parse: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+impl Run {
+    /// The first part that has no answer.
+    pub fn failure(&self) -> Option<(u8, &SolveError)> {
+        self.parts
+            .iter()
+            .find_map(|part| part.answer.as_ref().err().map(|e| (part.part, e)))
+    }
+}
+
+/// Object safe view of a [`Solution`] so the registry can hold every day in one list.
+pub trait Runner {
+    /// [`Solution::DAY`].
+    fn day(&self) -> u8;
+
+    /// Parses `input` once and answers the selected parts in order. Timing is wall clock, taken
+    /// separately for the parse and for each part, so [`Run::parse`] doesn't count in any
+    /// [`PartResult::elapsed`]; reading the input isn't timed at all. A part without an answer
+    /// is still timed and the next part still runs, but a parse error stops the run.
+    fn run(&self, input: &str, parts: Parts) -> Result<Run, ParseError>;
+}
+
+impl<S: Solution> Runner for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn run(&self, input: &str, parts: Parts) -> Result<Run, ParseError> {
+        let start = Instant::now();
+        let parsed = self.parse(input)?;
+        let parse = start.elapsed();
+
+        let mut results = vec![];
+        for part in 1..=2 {
+            if !parts.includes(part) {
+                continue;
+            }
+
+            let start = Instant::now();
+            let answer = if part == 1 {
+                self.part1(&parsed)
+            } else {
+                self.part2(&parsed)
+            };
+            results.push(PartResult {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            });
+        }
+
+        Ok(Run {
+            parse,
+            parts: results,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{parse_number, ParseError, Span};
+    use crate::solution::{Answer, Parts, Run, Runner, Solution, SolveError};
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 99;
+
+        type Input = Vec<u32>;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+            input
+                .lines()
+                .enumerate()
+                .map(|(i, l)| parse_number(i + 1, Span::line(l)))
+                .collect()
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+            Ok(input.iter().sum::<u32>().into())
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Answer, SolveError> {
+            if input.is_empty() {
+                return Err(SolveError::NoAnswer("there are no numbers".to_string()));
+            }
+            Ok(format!("{} numbers", input.len()).into())
+        }
+    }
+
+    fn answers_of(run: Run) -> Vec<(u8, Result<Answer, SolveError>)> {
+        run.parts.into_iter().map(|p| (p.part, p.answer)).collect()
+    }
+
+    #[test]
+    fn runner_answers_selected_parts() {
+        let runner: Box<dyn Runner> = Box::new(Sum);
+        assert_eq!(runner.day(), 99);
+
+        let answers = answers_of(runner.run("1\n2\n3", Parts::Both).unwrap());
+        assert_eq!(
+            answers,
+            vec![
+                (1, Ok(Answer::Unsigned(6))),
+                (2, Ok(Answer::Text("3 numbers".to_string())))
+            ]
+        );
+
+        let answers = answers_of(runner.run("1\n2\n3", Parts::Two).unwrap());
+        assert_eq!(
+            answers,
+            vec![(2, Ok(Answer::Text("3 numbers".to_string())))]
+        );
+
+        let err = runner.run("1\nx\n3", Parts::Both).unwrap_err();
+        assert_eq!(err, ParseError::new(2, 1, "x", "expected a number"));
+    }
+
+    #[test]
+    fn runner_reports_parts_without_answers() {
+        let run = Sum.run("", Parts::Both).unwrap();
+        let error = SolveError::NoAnswer("there are no numbers".to_string());
+        assert_eq!(
+            answers_of(run.clone()),
+            vec![(1, Ok(Answer::Unsigned(0))), (2, Err(error.clone()))]
+        );
+        assert_eq!(run.failure(), Some((2, &error)));
+        assert_eq!(error.to_string(), "there are no numbers");
+    }
+}
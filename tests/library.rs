@@ -0,0 +1,42 @@
+use aoc2021::day4::{BingoBoard, Tournament};
+use aoc2021::day5::{parse_vents, SeaFloor};
+use aoc2021::day6::Sea;
+use aoc2021::day7::Crabs;
+use aoc2021::day8::get_mapping;
+use aoc2021::registry::registry;
+use aoc2021::solution::{Answer, Parts};
+
+#[test]
+fn days_run_through_the_registry() {
+    let days = registry();
+    let day6 = days.iter().find(|d| d.day() == 6).unwrap();
+
+    let run = day6.run("3,4,3,1,2", Parts::One).unwrap();
+    assert_eq!(run.parts.len(), 1);
+    assert_eq!(run.parts[0].answer, Ok(Answer::Unsigned(5934)));
+}
+
+#[test]
+fn day_types_are_usable_directly() {
+    let mut tournament = Tournament::from(vec![
+        BingoBoard::from(vec![1, 2, 3, 4], 2, 2),
+        BingoBoard::from(vec![5, 6, 7, 8], 2, 2),
+    ]);
+    assert_eq!(tournament.call(1), 0);
+    assert_eq!(tournament.call(3), 1);
+    assert_eq!(tournament.winners()[0].score().map(|s| s.points), Ok(6 * 3));
+
+    let vents = parse_vents("0,0 -> 2,0\n1,0 -> 1,2".lines()).unwrap();
+    assert_eq!(SeaFloor::map(&vents, 3, false).dangerous_areas(), 1);
+
+    let mut sea = Sea::from(vec![0, 1]);
+    sea.day();
+    assert_eq!(sea.population(), 3);
+
+    let crabs = Crabs::from(vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14]);
+    assert_eq!(crabs.find_simple_maneuver(), Some((2, 37)));
+
+    let mapping =
+        get_mapping("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab").unwrap();
+    assert_eq!(mapping[&'1'], "ab");
+}